@@ -0,0 +1,192 @@
+mod allocator;
+
+use crate::allocator::PeakAllocator;
+use clap::Parser;
+use humantime::format_duration;
+use kaspa_consensus_core::constants::SOMPI_PER_KASPA;
+use log::info;
+use simply_kaspa_utxo_exporter_database::client::KaspaDbClient;
+use simply_kaspa_utxo_exporter_database::models::top_script::TopScript;
+use simply_kaspa_utxo_exporter_scan::aggregate::{aggregate, AggregationOptions, ScriptClass};
+use simply_kaspa_utxo_exporter_scan::fixture::{GeneratedSource, Generator};
+use simply_kaspa_utxo_exporter_scan::metrics::dormancy::BlockRate;
+use simply_kaspa_utxo_exporter_scan::scanner::{scan, ScanOptions};
+use simply_kaspa_utxo_exporter_scan::source::UtxoSource;
+use simply_kaspa_utxo_exporter_scan::tier_scheme::TierScheme;
+use std::env;
+use std::hint::black_box;
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
+
+#[global_allocator]
+static ALLOCATOR: PeakAllocator = PeakAllocator;
+
+const DAA_SCORE: u64 = 300_000_000;
+
+#[derive(Parser, Clone, Debug)]
+#[command(name = "simply-kaspa-utxo-exporter-bench")]
+struct BenchArgs {
+    #[clap(long, default_value = "10000000", help = "Number of generated UTXOs")]
+    utxos: u64,
+    #[clap(long, default_value = "1000000", help = "Number of distinct scripts the UTXOs are spread over")]
+    scripts: u64,
+    #[clap(long, default_value = "1.0", help = "Script reuse skew, 1.0 = even, larger values concentrate UTXOs on fewer scripts")]
+    script_skew: f64,
+    #[clap(long, default_value = "0", help = "Seed of the generated UTXO set")]
+    seed: u64,
+    #[clap(long, default_value = "10000", help = "Ignore utxos with amounts less than this (in sompi)")]
+    ignore_dust_amounts: u64,
+    #[clap(long, default_value = "default=log:10:100000000", help = "Tier schemes for the full scan")]
+    tier_schemes: Vec<TierScheme>,
+    #[clap(long, default_value = "1000", help = "Number of top scripts to select and insert (0 = all)")]
+    top_scripts_count: u64,
+    #[clap(long, default_value = "1000", help = "Number of largest individual UTXOs for the full scan (0 = disabled)")]
+    top_utxos_count: u64,
+    #[clap(
+        long,
+        default_value = "10000",
+        help = "Collect consolidation stats for scripts with at least this many UTXOs in the full scan"
+    )]
+    utxo_count_threshold: u64,
+    #[clap(long, default_value = "3", help = "Repetitions of the in-memory stages, the fastest is reported")]
+    iterations: u32,
+    #[clap(long, help = "Scratch PostgreSQL url to measure insertion throughput. WARNING: Its tables are emptied")]
+    database_url: Option<String>,
+    #[clap(short, long, default_value = "info", help = "error, warn, info, debug, trace, off")]
+    log_level: String,
+}
+
+struct Measurement {
+    elapsed: Duration,
+    peak_bytes: usize,
+}
+
+fn measure<T>(f: impl FnOnce() -> T) -> (T, Measurement) {
+    let baseline = allocator::reset_peak();
+    let start_time = Instant::now();
+    let result = f();
+    let elapsed = start_time.elapsed();
+    (result, Measurement { elapsed, peak_bytes: allocator::peak().saturating_sub(baseline) })
+}
+
+/// Repeats the stage and keeps the fastest run
+fn measure_fastest<T>(iterations: u32, mut f: impl FnMut() -> T) -> (T, Measurement) {
+    let (mut result, mut fastest) = measure(&mut f);
+    for _ in 1..iterations {
+        let (r, m) = measure(&mut f);
+        if m.elapsed < fastest.elapsed {
+            (result, fastest) = (r, m);
+        }
+    }
+    (result, fastest)
+}
+
+fn report(stage: &str, items: u64, unit: &str, measurement: &Measurement) {
+    let per_sec = items as f64 / measurement.elapsed.as_secs_f64().max(1e-9);
+    info!(
+        "{stage}: {items} {unit} in {} ({per_sec:.0} {unit}/s), peak memory +{:.1} MiB",
+        format_duration(Duration::from_micros(measurement.elapsed.as_micros() as u64)),
+        measurement.peak_bytes as f64 / (1024.0 * 1024.0)
+    );
+}
+
+#[tokio::main]
+async fn main() {
+    let args = BenchArgs::parse();
+
+    env::set_var("RUST_LOG", &args.log_level);
+    env_logger::builder().target(env_logger::Target::Stdout).format_target(false).format_timestamp_millis().init();
+
+    info!("{} {:?}", env!("CARGO_PKG_NAME"), args);
+    let generator = Generator { utxo_count: args.utxos, script_count: args.scripts, script_skew: args.script_skew, seed: args.seed };
+    let source = GeneratedSource::new(DAA_SCORE, generator);
+    let run = AtomicBool::new(true);
+
+    let (_, m) = measure(|| {
+        for result in source.utxos().unwrap() {
+            black_box(result.unwrap());
+        }
+    });
+    report("Generate (baseline)", args.utxos, "UTXOs", &m);
+
+    let (aggregated, m) = measure(|| {
+        let options = ScanOptions {
+            ignore_dust_amounts: args.ignore_dust_amounts,
+            utxo_tier_schemes: vec![],
+            top_utxos_count: 0,
+            watched_scripts: vec![],
+            dormant_age: 0,
+            dormancy_thresholds: vec![],
+            genesis_daa_score: 0,
+            block_rate: BlockRate::constant(1),
+            utxo_count_threshold: u64::MAX,
+        };
+        scan(&source, options, &run).unwrap_or_else(|e| panic!("Aggregation failed: {e}"))
+    });
+    report("Aggregate script amounts", args.utxos, "UTXOs", &m);
+    info!(
+        "Aggregated {} scripts, {} dust UTXOs, {} KAS",
+        aggregated.script_amounts.len(),
+        aggregated.dust_count,
+        aggregated.total_amount / SOMPI_PER_KASPA
+    );
+    drop(aggregated);
+
+    let (result, m) = measure(|| {
+        let options = ScanOptions {
+            ignore_dust_amounts: args.ignore_dust_amounts,
+            utxo_tier_schemes: args.tier_schemes.clone(),
+            top_utxos_count: args.top_utxos_count as usize,
+            watched_scripts: vec![],
+            dormant_age: 0,
+            dormancy_thresholds: vec![],
+            genesis_daa_score: 0,
+            block_rate: BlockRate::constant(1),
+            utxo_count_threshold: args.utxo_count_threshold,
+        };
+        scan(&source, options, &run).unwrap_or_else(|e| panic!("Scan failed: {e}"))
+    });
+    report("Full scan", args.utxos, "UTXOs", &m);
+    info!("Collected consolidation stats for {} scripts", result.script_utxos.len());
+
+    let script_count = result.script_amounts.len() as u64;
+    let options = AggregationOptions {
+        tier_schemes: args.tier_schemes.clone(),
+        top_scripts_count: args.top_scripts_count as usize,
+        top_scripts_min_amount: 0,
+        exclusion_count: 0,
+        entities: false,
+    };
+    let (aggregation, m) = measure_fastest(args.iterations, || {
+        aggregate(&result.script_amounts, &options, |_| ScriptClass::default(), &run).unwrap_or_else(|e| panic!("{e}"))
+    });
+    report("Tiers and top scripts", script_count, "scripts", &m);
+    drop(result);
+
+    if let Some(url) = &args.database_url {
+        let rows: Vec<TopScript> = aggregation
+            .top_scripts
+            .into_iter()
+            .enumerate()
+            .map(|(rank, (amount, script_public_key))| TopScript {
+                timestamp: 0,
+                rank: rank as i32,
+                script_public_key,
+                script_public_key_address: None,
+                amount: (amount / SOMPI_PER_KASPA) as i64,
+                entity: None,
+                category: None,
+            })
+            .collect();
+        let mut db = KaspaDbClient::new(url).await.unwrap_or_else(|e| panic!("Failed to connect to {url}: {e}"));
+        db.create_tables().await.unwrap_or_else(|e| panic!("Failed to create tables: {e}"));
+        db.empty_tables().await.unwrap_or_else(|e| panic!("Failed to empty tables: {e}"));
+        let baseline = allocator::reset_peak();
+        let start_time = Instant::now();
+        let inserted = db.insert_top_scripts(&rows).await.unwrap_or_else(|e| panic!("Insert failed: {e}"));
+        let m = Measurement { elapsed: start_time.elapsed(), peak_bytes: allocator::peak().saturating_sub(baseline) };
+        report("Insert top scripts", inserted, "rows", &m);
+        db.empty_tables().await.unwrap_or_else(|e| panic!("Failed to empty tables: {e}"));
+        db.close().await.unwrap();
+    }
+}
@@ -0,0 +1,49 @@
+use simply_kaspa_utxo_exporter_cli::cli_args::CliArgs;
+
+fn args(args: &[&str]) -> Vec<String> {
+    ["simply-kaspa-utxo-exporter"].iter().chain(args).map(|a| a.to_string()).collect()
+}
+
+#[test]
+fn command_line_takes_precedence() {
+    let config = "
+        # Comments and empty lines are ignored
+
+        --interval-minutes=15
+        --top-scripts-count 500
+        --webhook-url=https://example.com/a
+        --tier-migrations
+    ";
+    let cli_args =
+        CliArgs::parse_with_config(args(&["-i", "30", "--webhook-url", "https://example.com/b", "--tier-migrations"]), config)
+            .unwrap();
+    assert_eq!(cli_args.interval_minutes, 30);
+    assert_eq!(cli_args.top_scripts_count, 500);
+    // Repeatable options given on the command line replace the ones in the file
+    assert_eq!(cli_args.webhook_url, ["https://example.com/b"]);
+    assert!(cli_args.tier_migrations);
+
+    let cli_args = CliArgs::parse_with_config(args(&["--interval-minutes=45"]), "-i 15\n--webhook-url=https://example.com/a").unwrap();
+    assert_eq!(cli_args.interval_minutes, 45);
+    assert_eq!(cli_args.webhook_url, ["https://example.com/a"]);
+}
+
+#[test]
+fn invalid_config() {
+    assert!(CliArgs::parse_with_config(args(&[]), "--no-such-option").is_err());
+    assert!(CliArgs::parse_with_config(args(&[]), "--interval-minutes=soon").is_err());
+    assert!(CliArgs::parse_with_config(args(&[]), "--control-listen=127.0.0.1:8120").is_err());
+}
+
+#[test]
+fn startup_options_are_kept() {
+    let current = CliArgs::parse_with_config(args(&["--network", "testnet-10"]), "").unwrap();
+    let config = "--network=mainnet\n--database-url=postgres://other\n-i 5\n--amount-in-sompi\n--tier-scheme=coarse=100000000000";
+    let mut reloaded = CliArgs::parse_with_config(args(&["--network", "testnet-10"]), config).unwrap();
+    assert_eq!(reloaded.keep_startup_options(&current), ["--database-url", "--amount-in-sompi", "--tier-scheme"]);
+    assert_eq!(reloaded.network, "testnet-10");
+    assert_eq!(reloaded.database_url, current.database_url);
+    assert!(!reloaded.amount_in_sompi);
+    assert_eq!(reloaded.tier_schemes, current.tier_schemes);
+    assert_eq!(reloaded.interval_minutes, 5);
+}
@@ -6,9 +6,25 @@ use regex::Regex;
 use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
 use sqlx::{ConnectOptions, Error, Pool, Postgres};
 
+use crate::models::concentration_metrics::ConcentrationMetrics;
 use crate::models::distribution_tier::DistributionTier;
+use crate::models::dormant_supply::DormantSupply;
+use crate::models::entity_distribution_tier::EntityDistributionTier;
+use crate::models::excluded_balance::ExcludedBalance;
+use crate::models::lorenz_point::LorenzPoint;
+use crate::models::quarantined_snapshot::QuarantinedSnapshot;
 use crate::models::script_utxo_count::ScriptUtxoCount;
+use crate::models::skipped_run::SkippedRun;
+use crate::models::snapshot_anchor::SnapshotAnchor;
+use crate::models::supply_reconciliation::SupplyReconciliation;
+use crate::models::tier_migration::TierMigration;
+use crate::models::top_dormant_script::TopDormantScript;
+use crate::models::top_entity::TopEntity;
 use crate::models::top_script::TopScript;
+use crate::models::top_script_change::TopScriptChange;
+use crate::models::top_utxo::TopUtxo;
+use crate::models::utxo_distribution_tier::UtxoDistributionTier;
+use crate::models::watchlist_balance::WatchlistBalance;
 use crate::query;
 
 #[derive(Clone)]
@@ -19,17 +35,17 @@ pub struct KaspaDbClient {
 
 impl KaspaDbClient {
     pub async fn new(url: &str) -> Result<KaspaDbClient, Error> {
-        let url_cleaned = Regex::new(r"(postgres://postgres:)[^@]+(@)").expect("Failed to parse url").replace(url, "$1***$2");
+        let url_cleaned = clean_url(url);
         debug!("Connecting to PostgreSQL {}", url_cleaned);
-        let connect_opts = PgConnectOptions::from_str(url)?.log_slow_statements(LevelFilter::Warn, Duration::from_secs(60));
-        let pool = PgPoolOptions::new()
-            .acquire_timeout(Duration::from_secs(10))
-            .min_connections(0)
-            .max_connections(10)
-            .connect_with(connect_opts)
-            .await?;
+        let pool = pool_options().connect_with(connect_options(url)?).await?;
         info!("Connected to PostgreSQL {}", url_cleaned);
-        Ok(KaspaDbClient { pool, url_cleaned: url_cleaned.to_string() })
+        Ok(KaspaDbClient { pool, url_cleaned })
+    }
+
+    /// Creates the client without connecting, connections are opened on first use
+    pub fn new_lazy(url: &str) -> Result<KaspaDbClient, Error> {
+        let pool = pool_options().connect_lazy_with(connect_options(url)?);
+        Ok(KaspaDbClient { pool, url_cleaned: clean_url(url) })
     }
 
     pub async fn close(&mut self) -> Result<(), Error> {
@@ -57,15 +73,111 @@ impl KaspaDbClient {
         query::select::select_last_distribution_tier(&self.pool).await
     }
 
+    pub async fn select_top_scripts(&self, timestamp: i64) -> Result<Vec<TopScript>, Error> {
+        query::select::select_top_scripts(timestamp, &self.pool).await
+    }
+
+    pub async fn select_snapshot_totals(&self, timestamp: i64) -> Result<Option<(i64, i64)>, Error> {
+        query::select::select_snapshot_totals(timestamp, &self.pool).await
+    }
+
+    pub async fn select_dormant_supply(&self, timestamp: i64) -> Result<Vec<(String, i64, i64)>, Error> {
+        query::select::select_dormant_supply(timestamp, &self.pool).await
+    }
+
+    pub async fn select_snapshot_anchor(&self, timestamp: i64) -> Result<Option<(String, Vec<u8>)>, Error> {
+        query::select::select_snapshot_anchor(timestamp, &self.pool).await
+    }
+
+    pub async fn select_watchlist(&self) -> Result<Vec<String>, Error> {
+        query::select::select_watchlist(&self.pool).await
+    }
+
     pub async fn insert_distribution_tiers(&self, distribution_tiers: &[DistributionTier]) -> Result<u64, Error> {
         query::insert::insert_distribution_tiers(distribution_tiers, &self.pool).await
     }
 
+    pub async fn insert_utxo_distribution_tiers(&self, utxo_distribution_tiers: &[UtxoDistributionTier]) -> Result<u64, Error> {
+        query::insert::insert_utxo_distribution_tiers(utxo_distribution_tiers, &self.pool).await
+    }
+
+    pub async fn insert_tier_migrations(&self, tier_migrations: &[TierMigration]) -> Result<u64, Error> {
+        query::insert::insert_tier_migrations(tier_migrations, &self.pool).await
+    }
+
     pub async fn insert_top_scripts(&self, insert_top_scripts: &[TopScript]) -> Result<u64, Error> {
         query::insert::insert_top_scripts(insert_top_scripts, &self.pool).await
     }
 
+    pub async fn insert_top_entities(&self, top_entities: &[TopEntity]) -> Result<u64, Error> {
+        query::insert::insert_top_entities(top_entities, &self.pool).await
+    }
+
+    pub async fn insert_entity_distribution_tiers(&self, entity_distribution_tiers: &[EntityDistributionTier]) -> Result<u64, Error> {
+        query::insert::insert_entity_distribution_tiers(entity_distribution_tiers, &self.pool).await
+    }
+
+    pub async fn insert_top_script_changes(&self, top_script_changes: &[TopScriptChange]) -> Result<u64, Error> {
+        query::insert::insert_top_script_changes(top_script_changes, &self.pool).await
+    }
+
+    pub async fn insert_top_utxos(&self, top_utxos: &[TopUtxo]) -> Result<u64, Error> {
+        query::insert::insert_top_utxos(top_utxos, &self.pool).await
+    }
+
+    pub async fn insert_concentration_metrics(&self, concentration_metrics: &ConcentrationMetrics) -> Result<u64, Error> {
+        query::insert::insert_concentration_metrics(concentration_metrics, &self.pool).await
+    }
+
+    pub async fn insert_supply_reconciliation(&self, supply_reconciliation: &SupplyReconciliation) -> Result<u64, Error> {
+        query::insert::insert_supply_reconciliation(supply_reconciliation, &self.pool).await
+    }
+
+    pub async fn insert_quarantined_snapshot(&self, quarantined_snapshot: &QuarantinedSnapshot) -> Result<u64, Error> {
+        query::insert::insert_quarantined_snapshot(quarantined_snapshot, &self.pool).await
+    }
+
+    pub async fn insert_dormant_supply(&self, dormant_supply: &[DormantSupply]) -> Result<u64, Error> {
+        query::insert::insert_dormant_supply(dormant_supply, &self.pool).await
+    }
+
+    pub async fn insert_top_dormant_scripts(&self, top_dormant_scripts: &[TopDormantScript]) -> Result<u64, Error> {
+        query::insert::insert_top_dormant_scripts(top_dormant_scripts, &self.pool).await
+    }
+
+    pub async fn insert_skipped_run(&self, skipped_run: &SkippedRun) -> Result<u64, Error> {
+        query::insert::insert_skipped_run(skipped_run, &self.pool).await
+    }
+
+    pub async fn insert_snapshot_anchor(&self, snapshot_anchor: &SnapshotAnchor) -> Result<u64, Error> {
+        query::insert::insert_snapshot_anchor(snapshot_anchor, &self.pool).await
+    }
+
+    pub async fn insert_lorenz_curve(&self, lorenz_curve: &[LorenzPoint]) -> Result<u64, Error> {
+        query::insert::insert_lorenz_curve(lorenz_curve, &self.pool).await
+    }
+
+    pub async fn insert_watchlist_balances(&self, watchlist_balances: &[WatchlistBalance]) -> Result<u64, Error> {
+        query::insert::insert_watchlist_balances(watchlist_balances, &self.pool).await
+    }
+
+    pub async fn insert_excluded_balances(&self, excluded_balances: &[ExcludedBalance]) -> Result<u64, Error> {
+        query::insert::insert_excluded_balances(excluded_balances, &self.pool).await
+    }
+
     pub async fn replace_script_utxo_counts(&self, script_utxo_counts: &[ScriptUtxoCount]) -> Result<u64, Error> {
         query::upsert::replace_script_utxo_counts(script_utxo_counts, &self.pool).await
     }
 }
+
+fn clean_url(url: &str) -> String {
+    Regex::new(r"(postgres://postgres:)[^@]+(@)").expect("Failed to parse url").replace(url, "$1***$2").to_string()
+}
+
+fn connect_options(url: &str) -> Result<PgConnectOptions, Error> {
+    Ok(PgConnectOptions::from_str(url)?.log_slow_statements(LevelFilter::Warn, Duration::from_secs(60)))
+}
+
+fn pool_options() -> PgPoolOptions {
+    PgPoolOptions::new().acquire_timeout(Duration::from_secs(10)).min_connections(0).max_connections(10)
+}
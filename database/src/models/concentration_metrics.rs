@@ -0,0 +1,14 @@
+pub struct ConcentrationMetrics {
+    pub timestamp: i64,
+    pub script_count: i64,
+    pub total_amount: i64,
+    pub gini: f64,
+    pub hhi: f64,
+    pub scripts_50_percent: i64,
+    pub scripts_90_percent: i64,
+    /// Balance percentiles, fractional KAS unless amounts are stored in sompi
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub p999: f64,
+}
@@ -1,7 +1,13 @@
-#[derive(Eq, PartialEq, Hash)]
+#[derive(PartialEq)]
 pub struct DistributionTier {
     pub timestamp: i64,
+    pub scheme: String,
     pub tier: i16,
+    pub lower_bound: i64,
+    pub upper_bound: Option<i64>,
     pub count: i64,
     pub amount: i64,
+    pub utxo_count: i64,
+    pub avg_utxo_amount: f64,
+    pub avg_utxos_per_script: f64,
 }
@@ -0,0 +1,12 @@
+pub struct DormantSupply {
+    pub timestamp: i64,
+    pub threshold: String,
+    pub max_block_daa_score: i64,
+    pub script_count: i64,
+    pub utxo_count: i64,
+    pub amount: i64,
+    /// Exact amount regardless of --amount-in-sompi, the next run computes the reawakened amount from it
+    pub amount_sompi: i64,
+    pub supply_percent: f64,
+    pub reawakened_amount: Option<i64>,
+}
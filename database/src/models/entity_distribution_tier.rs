@@ -0,0 +1,11 @@
+#[derive(Eq, PartialEq, Hash)]
+pub struct EntityDistributionTier {
+    pub timestamp: i64,
+    pub scheme: String,
+    pub tier: i16,
+    pub lower_bound: i64,
+    pub upper_bound: Option<i64>,
+    pub count: i64,
+    pub amount: i64,
+    pub utxo_count: i64,
+}
@@ -0,0 +1,9 @@
+#[derive(Eq, PartialEq, Hash)]
+pub struct ExcludedBalance {
+    pub timestamp: i64,
+    pub rule_type: String,
+    pub rule: String,
+    pub script_count: i64,
+    pub utxo_count: i64,
+    pub amount: i64,
+}
@@ -0,0 +1,5 @@
+pub struct LorenzPoint {
+    pub timestamp: i64,
+    pub population_share: f64,
+    pub supply_share: f64,
+}
@@ -1,3 +1,19 @@
+pub mod concentration_metrics;
 pub mod distribution_tier;
+pub mod dormant_supply;
+pub mod entity_distribution_tier;
+pub mod excluded_balance;
+pub mod lorenz_point;
+pub mod quarantined_snapshot;
 pub mod script_utxo_count;
+pub mod skipped_run;
+pub mod snapshot_anchor;
+pub mod supply_reconciliation;
+pub mod tier_migration;
+pub mod top_dormant_script;
+pub mod top_entity;
 pub mod top_script;
+pub mod top_script_change;
+pub mod top_utxo;
+pub mod utxo_distribution_tier;
+pub mod watchlist_balance;
@@ -0,0 +1,8 @@
+pub struct QuarantinedSnapshot {
+    pub timestamp: i64,
+    pub virtual_daa_score: i64,
+    pub total_supply: i64,
+    pub script_count: i64,
+    pub top_10_replaced: i32,
+    pub reasons: String,
+}
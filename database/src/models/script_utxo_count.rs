@@ -1,5 +1,15 @@
+/// Consolidation stats of a script, `count` and the amounts cover the UTXOs at or above the dust limit,
+/// `dust_count` the ones below it, the DAA score range covers both
 pub struct ScriptUtxoCount {
     pub script_public_key: Vec<u8>,
     pub script_public_key_address: Option<String>,
     pub count: i64,
+    pub amount: i64,
+    pub min_amount: i64,
+    pub max_amount: i64,
+    /// Estimated from a sample of the amounts for scripts with many UTXOs
+    pub median_amount: i64,
+    pub dust_count: i64,
+    pub oldest_block_daa_score: i64,
+    pub newest_block_daa_score: i64,
 }
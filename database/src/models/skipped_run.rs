@@ -0,0 +1,7 @@
+pub struct SkippedRun {
+    pub timestamp: i64,
+    pub reason: String,
+    pub virtual_daa_score: i64,
+    pub virtual_lag_seconds: i64,
+    pub pruning_point_age_seconds: i64,
+}
@@ -0,0 +1,7 @@
+pub struct SnapshotAnchor {
+    pub timestamp: i64,
+    pub mode: String,
+    pub block_hash: Vec<u8>,
+    pub daa_score: i64,
+    pub target_daa_score: Option<i64>,
+}
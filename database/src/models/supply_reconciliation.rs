@@ -0,0 +1,9 @@
+pub struct SupplyReconciliation {
+    pub timestamp: i64,
+    pub virtual_daa_score: i64,
+    pub expected_supply: i64,
+    pub scanned_supply: i64,
+    pub difference: i64,
+    pub difference_percent: f64,
+    pub suspicious: bool,
+}
@@ -0,0 +1,11 @@
+#[derive(Eq, PartialEq, Hash)]
+pub struct TierMigration {
+    pub timestamp: i64,
+    pub previous_timestamp: i64,
+    pub scheme: String,
+    pub from_tier: i16,
+    pub to_tier: i16,
+    pub count: i64,
+    pub amount: i64,
+    pub previous_amount: i64,
+}
@@ -0,0 +1,9 @@
+pub struct TopDormantScript {
+    pub timestamp: i64,
+    pub rank: i32,
+    pub script_public_key: Vec<u8>,
+    pub script_public_key_address: Option<String>,
+    pub dormant_amount: i64,
+    pub amount: i64,
+    pub oldest_block_daa_score: i64,
+}
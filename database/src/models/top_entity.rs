@@ -0,0 +1,11 @@
+#[derive(Eq, PartialEq, Hash)]
+pub struct TopEntity {
+    pub timestamp: i64,
+    pub rank: i32,
+    pub entity: Option<String>,
+    pub category: Option<String>,
+    pub script_public_key: Option<Vec<u8>>,
+    pub script_public_key_address: Option<String>,
+    pub script_count: i64,
+    pub amount: i64,
+}
@@ -5,4 +5,6 @@ pub struct TopScript {
     pub script_public_key: Vec<u8>,
     pub script_public_key_address: Option<String>,
     pub amount: i64,
+    pub entity: Option<String>,
+    pub category: Option<String>,
 }
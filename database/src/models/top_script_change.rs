@@ -0,0 +1,14 @@
+#[derive(Eq, PartialEq, Hash)]
+pub struct TopScriptChange {
+    pub timestamp: i64,
+    pub previous_timestamp: i64,
+    pub script_public_key: Vec<u8>,
+    pub script_public_key_address: Option<String>,
+    pub change: String,
+    pub rank: Option<i32>,
+    pub previous_rank: Option<i32>,
+    pub rank_change: Option<i32>,
+    pub amount: i64,
+    pub previous_amount: Option<i64>,
+    pub amount_change: Option<i64>,
+}
@@ -0,0 +1,12 @@
+#[derive(Eq, PartialEq, Hash)]
+pub struct TopUtxo {
+    pub timestamp: i64,
+    pub rank: i32,
+    pub transaction_id: Vec<u8>,
+    pub index: i64,
+    pub amount: i64,
+    pub script_public_key: Vec<u8>,
+    pub script_public_key_address: Option<String>,
+    pub block_daa_score: i64,
+    pub is_coinbase: bool,
+}
@@ -0,0 +1,10 @@
+#[derive(Eq, PartialEq, Hash)]
+pub struct UtxoDistributionTier {
+    pub timestamp: i64,
+    pub scheme: String,
+    pub tier: i16,
+    pub lower_bound: i64,
+    pub upper_bound: Option<i64>,
+    pub count: i64,
+    pub amount: i64,
+}
@@ -0,0 +1,8 @@
+#[derive(Eq, PartialEq, Hash)]
+pub struct WatchlistBalance {
+    pub timestamp: i64,
+    pub address: String,
+    pub script_public_key: Vec<u8>,
+    pub amount: i64,
+    pub utxo_count: i64,
+}
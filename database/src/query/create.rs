@@ -1,16 +1,50 @@
+use log::info;
 use sqlx::{Error, Pool, Postgres};
 
 pub async fn empty_tables(pool: &Pool<Postgres>) -> Result<(), Error> {
     empty_table(pool, "distribution_tiers").await?;
+    empty_table(pool, "utxo_distribution_tiers").await?;
+    empty_table(pool, "tier_migrations").await?;
     empty_table(pool, "top_scripts").await?;
+    empty_table(pool, "top_entities").await?;
+    empty_table(pool, "entity_distribution_tiers").await?;
+    empty_table(pool, "top_utxos").await?;
+    empty_table(pool, "top_script_changes").await?;
     empty_table(pool, "script_utxo_counts").await?;
+    empty_table(pool, "concentration_metrics").await?;
+    empty_table(pool, "lorenz_curve").await?;
+    empty_table(pool, "watchlist_balances").await?;
+    empty_table(pool, "excluded_balances").await?;
+    empty_table(pool, "supply_reconciliation").await?;
+    empty_table(pool, "quarantined_snapshots").await?;
+    empty_table(pool, "dormant_supply").await?;
+    empty_table(pool, "top_dormant_scripts").await?;
+    empty_table(pool, "skipped_runs").await?;
+    empty_table(pool, "snapshot_anchors").await?;
     Ok(())
 }
 
 pub async fn create_tables(pool: &Pool<Postgres>) -> Result<(), Error> {
     create_distribution_tiers(pool).await?;
+    create_utxo_distribution_tiers(pool).await?;
+    create_tier_migrations(pool).await?;
     create_top_scripts(pool).await?;
+    create_top_entities(pool).await?;
+    create_entity_distribution_tiers(pool).await?;
+    create_top_utxos(pool).await?;
+    create_top_script_changes(pool).await?;
     create_script_utxo_counts(pool).await?;
+    create_concentration_metrics(pool).await?;
+    create_lorenz_curve(pool).await?;
+    create_watchlist(pool).await?;
+    create_watchlist_balances(pool).await?;
+    create_excluded_balances(pool).await?;
+    create_supply_reconciliation(pool).await?;
+    create_quarantined_snapshots(pool).await?;
+    create_dormant_supply(pool).await?;
+    create_top_dormant_scripts(pool).await?;
+    create_skipped_runs(pool).await?;
+    create_snapshot_anchors(pool).await?;
     Ok(())
 }
 
@@ -26,10 +60,94 @@ async fn create_distribution_tiers(pool: &Pool<Postgres>) -> Result<(), Error> {
         sqlx::query(
             "CREATE TABLE IF NOT EXISTS distribution_tiers (
                 timestamp BIGINT,
+                scheme VARCHAR,
                 tier SMALLINT,
+                lower_bound BIGINT,
+                upper_bound BIGINT,
                 count BIGINT,
                 amount BIGINT,
-                PRIMARY KEY (timestamp, tier)
+                utxo_count BIGINT,
+                avg_utxo_amount DOUBLE PRECISION,
+                avg_utxos_per_script DOUBLE PRECISION,
+                PRIMARY KEY (timestamp, scheme, tier)
+            )",
+        )
+        .execute(pool)
+        .await?;
+        return Ok(());
+    }
+    if !column_exists(pool, "distribution_tiers", "scheme").await? {
+        info!("Adding tier scheme columns to distribution_tiers");
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            "ALTER TABLE distribution_tiers
+                ADD COLUMN scheme VARCHAR NOT NULL DEFAULT 'default',
+                ADD COLUMN lower_bound BIGINT,
+                ADD COLUMN upper_bound BIGINT",
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            "UPDATE distribution_tiers SET
+                lower_bound = CASE WHEN tier = 0 THEN 0 ELSE (10 ^ (tier - 1))::BIGINT * 100000000 END,
+                upper_bound = (10 ^ tier)::BIGINT * 100000000",
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            "ALTER TABLE distribution_tiers DROP CONSTRAINT distribution_tiers_pkey, ADD PRIMARY KEY (timestamp, scheme, tier)",
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+    }
+    if !column_exists(pool, "distribution_tiers", "utxo_count").await? {
+        info!("Adding utxo count columns to distribution_tiers");
+        sqlx::query(
+            "ALTER TABLE distribution_tiers
+                ADD COLUMN utxo_count BIGINT,
+                ADD COLUMN avg_utxo_amount DOUBLE PRECISION,
+                ADD COLUMN avg_utxos_per_script DOUBLE PRECISION",
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn create_utxo_distribution_tiers(pool: &Pool<Postgres>) -> Result<(), Error> {
+    if !table_exists(pool, "utxo_distribution_tiers").await? {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS utxo_distribution_tiers (
+                timestamp BIGINT,
+                scheme VARCHAR,
+                tier SMALLINT,
+                lower_bound BIGINT,
+                upper_bound BIGINT,
+                count BIGINT,
+                amount BIGINT,
+                PRIMARY KEY (timestamp, scheme, tier)
+            )",
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn create_tier_migrations(pool: &Pool<Postgres>) -> Result<(), Error> {
+    if !table_exists(pool, "tier_migrations").await? {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS tier_migrations (
+                timestamp BIGINT,
+                previous_timestamp BIGINT,
+                scheme VARCHAR,
+                from_tier SMALLINT,
+                to_tier SMALLINT,
+                count BIGINT,
+                amount BIGINT,
+                previous_amount BIGINT,
+                PRIMARY KEY (timestamp, scheme, from_tier, to_tier)
             )",
         )
         .execute(pool)
@@ -47,6 +165,32 @@ async fn create_top_scripts(pool: &Pool<Postgres>) -> Result<(), Error> {
                 script_public_key BYTEA,
                 script_public_key_address VARCHAR,
                 amount BIGINT,
+                entity VARCHAR,
+                category VARCHAR,
+                PRIMARY KEY (timestamp, rank)
+            )",
+        )
+        .execute(pool)
+        .await?;
+    } else if !column_exists(pool, "top_scripts", "entity").await? {
+        info!("Adding label columns to top_scripts");
+        sqlx::query("ALTER TABLE top_scripts ADD COLUMN entity VARCHAR, ADD COLUMN category VARCHAR").execute(pool).await?;
+    }
+    Ok(())
+}
+
+async fn create_top_entities(pool: &Pool<Postgres>) -> Result<(), Error> {
+    if !table_exists(pool, "top_entities").await? {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS top_entities (
+                timestamp BIGINT,
+                rank INT,
+                entity VARCHAR,
+                category VARCHAR,
+                script_public_key BYTEA,
+                script_public_key_address VARCHAR,
+                script_count BIGINT,
+                amount BIGINT,
                 PRIMARY KEY (timestamp, rank)
             )",
         )
@@ -56,6 +200,73 @@ async fn create_top_scripts(pool: &Pool<Postgres>) -> Result<(), Error> {
     Ok(())
 }
 
+async fn create_entity_distribution_tiers(pool: &Pool<Postgres>) -> Result<(), Error> {
+    if !table_exists(pool, "entity_distribution_tiers").await? {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS entity_distribution_tiers (
+                timestamp BIGINT,
+                scheme VARCHAR,
+                tier SMALLINT,
+                lower_bound BIGINT,
+                upper_bound BIGINT,
+                count BIGINT,
+                amount BIGINT,
+                utxo_count BIGINT,
+                PRIMARY KEY (timestamp, scheme, tier)
+            )",
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn create_top_utxos(pool: &Pool<Postgres>) -> Result<(), Error> {
+    if !table_exists(pool, "top_utxos").await? {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS top_utxos (
+                timestamp BIGINT,
+                rank INT,
+                transaction_id BYTEA,
+                index BIGINT,
+                amount BIGINT,
+                script_public_key BYTEA,
+                script_public_key_address VARCHAR,
+                block_daa_score BIGINT,
+                is_coinbase BOOLEAN,
+                PRIMARY KEY (timestamp, rank)
+            )",
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn create_top_script_changes(pool: &Pool<Postgres>) -> Result<(), Error> {
+    if !table_exists(pool, "top_script_changes").await? {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS top_script_changes (
+                timestamp BIGINT,
+                previous_timestamp BIGINT,
+                script_public_key BYTEA,
+                script_public_key_address VARCHAR,
+                change VARCHAR,
+                rank INT,
+                previous_rank INT,
+                rank_change INT,
+                amount BIGINT,
+                previous_amount BIGINT,
+                amount_change BIGINT,
+                PRIMARY KEY (timestamp, script_public_key)
+            )",
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
 async fn create_script_utxo_counts(pool: &Pool<Postgres>) -> Result<(), Error> {
     if !table_exists(pool, "script_utxo_counts").await? {
         sqlx::query(
@@ -63,15 +274,259 @@ async fn create_script_utxo_counts(pool: &Pool<Postgres>) -> Result<(), Error> {
                 script_public_key BYTEA,
                 script_public_key_address VARCHAR,
                 count BIGINT,
+                amount BIGINT,
+                min_amount BIGINT,
+                max_amount BIGINT,
+                median_amount BIGINT,
+                dust_count BIGINT,
+                oldest_block_daa_score BIGINT,
+                newest_block_daa_score BIGINT,
                 PRIMARY KEY (script_public_key)
             )",
         )
         .execute(pool)
         .await?;
+    } else if !column_exists(pool, "script_utxo_counts", "amount").await? {
+        info!("Adding consolidation columns to script_utxo_counts");
+        sqlx::query(
+            "ALTER TABLE script_utxo_counts
+                ADD COLUMN amount BIGINT,
+                ADD COLUMN min_amount BIGINT,
+                ADD COLUMN max_amount BIGINT,
+                ADD COLUMN median_amount BIGINT,
+                ADD COLUMN dust_count BIGINT,
+                ADD COLUMN oldest_block_daa_score BIGINT,
+                ADD COLUMN newest_block_daa_score BIGINT",
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn create_concentration_metrics(pool: &Pool<Postgres>) -> Result<(), Error> {
+    if !table_exists(pool, "concentration_metrics").await? {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS concentration_metrics (
+                timestamp BIGINT,
+                script_count BIGINT,
+                total_amount BIGINT,
+                gini DOUBLE PRECISION,
+                hhi DOUBLE PRECISION,
+                scripts_50_percent BIGINT,
+                scripts_90_percent BIGINT,
+                p50 DOUBLE PRECISION,
+                p90 DOUBLE PRECISION,
+                p99 DOUBLE PRECISION,
+                p999 DOUBLE PRECISION,
+                PRIMARY KEY (timestamp)
+            )",
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn create_lorenz_curve(pool: &Pool<Postgres>) -> Result<(), Error> {
+    if !table_exists(pool, "lorenz_curve").await? {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS lorenz_curve (
+                timestamp BIGINT,
+                population_share DOUBLE PRECISION,
+                supply_share DOUBLE PRECISION,
+                PRIMARY KEY (timestamp, population_share)
+            )",
+        )
+        .execute(pool)
+        .await?;
     }
     Ok(())
 }
 
+async fn create_watchlist(pool: &Pool<Postgres>) -> Result<(), Error> {
+    if !table_exists(pool, "watchlist").await? {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS watchlist (
+                address VARCHAR,
+                PRIMARY KEY (address)
+            )",
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn create_watchlist_balances(pool: &Pool<Postgres>) -> Result<(), Error> {
+    if !table_exists(pool, "watchlist_balances").await? {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS watchlist_balances (
+                timestamp BIGINT,
+                address VARCHAR,
+                script_public_key BYTEA,
+                amount BIGINT,
+                utxo_count BIGINT,
+                PRIMARY KEY (timestamp, address)
+            )",
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn create_excluded_balances(pool: &Pool<Postgres>) -> Result<(), Error> {
+    if !table_exists(pool, "excluded_balances").await? {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS excluded_balances (
+                timestamp BIGINT,
+                rule_type VARCHAR,
+                rule VARCHAR,
+                script_count BIGINT,
+                utxo_count BIGINT,
+                amount BIGINT,
+                PRIMARY KEY (timestamp, rule_type, rule)
+            )",
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn create_supply_reconciliation(pool: &Pool<Postgres>) -> Result<(), Error> {
+    if !table_exists(pool, "supply_reconciliation").await? {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS supply_reconciliation (
+                timestamp BIGINT,
+                virtual_daa_score BIGINT,
+                expected_supply BIGINT,
+                scanned_supply BIGINT,
+                difference BIGINT,
+                difference_percent DOUBLE PRECISION,
+                suspicious BOOLEAN,
+                PRIMARY KEY (timestamp)
+            )",
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn create_quarantined_snapshots(pool: &Pool<Postgres>) -> Result<(), Error> {
+    if !table_exists(pool, "quarantined_snapshots").await? {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS quarantined_snapshots (
+                timestamp BIGINT,
+                virtual_daa_score BIGINT,
+                total_supply BIGINT,
+                script_count BIGINT,
+                top_10_replaced INTEGER,
+                reasons VARCHAR,
+                PRIMARY KEY (timestamp)
+            )",
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn create_dormant_supply(pool: &Pool<Postgres>) -> Result<(), Error> {
+    if !table_exists(pool, "dormant_supply").await? {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS dormant_supply (
+                timestamp BIGINT,
+                threshold VARCHAR,
+                max_block_daa_score BIGINT,
+                script_count BIGINT,
+                utxo_count BIGINT,
+                amount BIGINT,
+                amount_sompi BIGINT,
+                supply_percent DOUBLE PRECISION,
+                reawakened_amount BIGINT,
+                PRIMARY KEY (timestamp, threshold)
+            )",
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn create_top_dormant_scripts(pool: &Pool<Postgres>) -> Result<(), Error> {
+    if !table_exists(pool, "top_dormant_scripts").await? {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS top_dormant_scripts (
+                timestamp BIGINT,
+                rank INTEGER,
+                script_public_key BYTEA,
+                script_public_key_address VARCHAR,
+                dormant_amount BIGINT,
+                amount BIGINT,
+                oldest_block_daa_score BIGINT,
+                PRIMARY KEY (timestamp, rank)
+            )",
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn create_skipped_runs(pool: &Pool<Postgres>) -> Result<(), Error> {
+    if !table_exists(pool, "skipped_runs").await? {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS skipped_runs (
+                timestamp BIGINT,
+                reason VARCHAR,
+                virtual_daa_score BIGINT,
+                virtual_lag_seconds BIGINT,
+                pruning_point_age_seconds BIGINT,
+                PRIMARY KEY (timestamp)
+            )",
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn create_snapshot_anchors(pool: &Pool<Postgres>) -> Result<(), Error> {
+    if !table_exists(pool, "snapshot_anchors").await? {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS snapshot_anchors (
+                timestamp BIGINT,
+                mode VARCHAR,
+                block_hash BYTEA,
+                daa_score BIGINT,
+                target_daa_score BIGINT,
+                PRIMARY KEY (timestamp)
+            )",
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn column_exists(pool: &Pool<Postgres>, table: &str, column: &str) -> Result<bool, Error> {
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS (
+            SELECT 1
+            FROM information_schema.columns
+            WHERE table_name = $1 AND column_name = $2
+        )",
+    )
+    .bind(table)
+    .bind(column)
+    .fetch_one(pool)
+    .await?;
+    Ok(exists)
+}
+
 async fn table_exists(pool: &Pool<Postgres>, name: &str) -> Result<bool, Error> {
     let exists: bool = sqlx::query_scalar(
         format!(
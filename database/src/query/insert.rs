@@ -1,27 +1,133 @@
 use itertools::Itertools;
 use sqlx::{Error, Pool, Postgres};
 
+use crate::models::concentration_metrics::ConcentrationMetrics;
 use crate::models::distribution_tier::DistributionTier;
+use crate::models::dormant_supply::DormantSupply;
+use crate::models::entity_distribution_tier::EntityDistributionTier;
+use crate::models::excluded_balance::ExcludedBalance;
+use crate::models::lorenz_point::LorenzPoint;
+use crate::models::quarantined_snapshot::QuarantinedSnapshot;
+use crate::models::skipped_run::SkippedRun;
+use crate::models::snapshot_anchor::SnapshotAnchor;
+use crate::models::supply_reconciliation::SupplyReconciliation;
+use crate::models::tier_migration::TierMigration;
+use crate::models::top_dormant_script::TopDormantScript;
+use crate::models::top_entity::TopEntity;
 use crate::models::top_script::TopScript;
+use crate::models::top_script_change::TopScriptChange;
+use crate::models::top_utxo::TopUtxo;
+use crate::models::utxo_distribution_tier::UtxoDistributionTier;
+use crate::models::watchlist_balance::WatchlistBalance;
 
 pub async fn insert_distribution_tiers(distribution_tiers: &[DistributionTier], pool: &Pool<Postgres>) -> Result<u64, Error> {
-    const COLS: usize = 4;
-    let sql = format!(
-        "INSERT INTO distribution_tiers (timestamp, tier, count, amount) VALUES {} ON CONFLICT DO NOTHING",
-        generate_placeholders(distribution_tiers.len(), COLS)
-    );
-    let mut query = sqlx::query(&sql);
-    for dt in distribution_tiers {
-        query = query.bind(dt.timestamp);
-        query = query.bind(dt.tier);
-        query = query.bind(dt.count);
-        query = query.bind(dt.amount);
+    if distribution_tiers.is_empty() {
+        return Ok(0);
     }
-    Ok(query.execute(pool).await?.rows_affected())
+    const COLS: usize = 10;
+    const BATCH_SIZE: usize = 2_000;
+
+    let mut total_rows = 0u64;
+    let mut tx = pool.begin().await?;
+
+    for tiers_chunk in distribution_tiers.chunks(BATCH_SIZE) {
+        let sql = format!(
+            "INSERT INTO distribution_tiers (timestamp, scheme, tier, lower_bound, upper_bound, count, amount,
+                utxo_count, avg_utxo_amount, avg_utxos_per_script) VALUES {} ON CONFLICT DO NOTHING",
+            generate_placeholders(tiers_chunk.len(), COLS)
+        );
+        let mut query = sqlx::query(&sql);
+        for dt in tiers_chunk {
+            query = query.bind(dt.timestamp);
+            query = query.bind(&dt.scheme);
+            query = query.bind(dt.tier);
+            query = query.bind(dt.lower_bound);
+            query = query.bind(dt.upper_bound);
+            query = query.bind(dt.count);
+            query = query.bind(dt.amount);
+            query = query.bind(dt.utxo_count);
+            query = query.bind(dt.avg_utxo_amount);
+            query = query.bind(dt.avg_utxos_per_script);
+        }
+        total_rows += query.execute(&mut *tx).await?.rows_affected();
+    }
+    tx.commit().await?;
+    Ok(total_rows)
+}
+
+pub async fn insert_utxo_distribution_tiers(
+    utxo_distribution_tiers: &[UtxoDistributionTier],
+    pool: &Pool<Postgres>,
+) -> Result<u64, Error> {
+    if utxo_distribution_tiers.is_empty() {
+        return Ok(0);
+    }
+    const COLS: usize = 7;
+    const BATCH_SIZE: usize = 2_000;
+
+    let mut total_rows = 0u64;
+    let mut tx = pool.begin().await?;
+
+    for tiers_chunk in utxo_distribution_tiers.chunks(BATCH_SIZE) {
+        let sql = format!(
+            "INSERT INTO utxo_distribution_tiers (timestamp, scheme, tier, lower_bound, upper_bound, count, amount)
+             VALUES {} ON CONFLICT DO NOTHING",
+            generate_placeholders(tiers_chunk.len(), COLS)
+        );
+        let mut query = sqlx::query(&sql);
+        for dt in tiers_chunk {
+            query = query.bind(dt.timestamp);
+            query = query.bind(&dt.scheme);
+            query = query.bind(dt.tier);
+            query = query.bind(dt.lower_bound);
+            query = query.bind(dt.upper_bound);
+            query = query.bind(dt.count);
+            query = query.bind(dt.amount);
+        }
+        total_rows += query.execute(&mut *tx).await?.rows_affected();
+    }
+    tx.commit().await?;
+    Ok(total_rows)
+}
+
+pub async fn insert_tier_migrations(tier_migrations: &[TierMigration], pool: &Pool<Postgres>) -> Result<u64, Error> {
+    if tier_migrations.is_empty() {
+        return Ok(0);
+    }
+    const COLS: usize = 8;
+    const BATCH_SIZE: usize = 2_000;
+
+    let mut total_rows = 0u64;
+    let mut tx = pool.begin().await?;
+
+    for migrations_chunk in tier_migrations.chunks(BATCH_SIZE) {
+        let sql = format!(
+            "INSERT INTO tier_migrations (timestamp, previous_timestamp, scheme, from_tier, to_tier, count, amount, previous_amount)
+             VALUES {} ON CONFLICT DO NOTHING",
+            generate_placeholders(migrations_chunk.len(), COLS)
+        );
+        let mut query = sqlx::query(&sql);
+        for tm in migrations_chunk {
+            query = query.bind(tm.timestamp);
+            query = query.bind(tm.previous_timestamp);
+            query = query.bind(&tm.scheme);
+            query = query.bind(tm.from_tier);
+            query = query.bind(tm.to_tier);
+            query = query.bind(tm.count);
+            query = query.bind(tm.amount);
+            query = query.bind(tm.previous_amount);
+        }
+        total_rows += query.execute(&mut *tx).await?.rows_affected();
+    }
+    tx.commit().await?;
+    Ok(total_rows)
 }
 
 pub async fn insert_top_scripts(top_scripts: &[TopScript], pool: &Pool<Postgres>) -> Result<u64, Error> {
-    const COLS: usize = 5;
+    if top_scripts.is_empty() {
+        return Ok(0);
+    }
+    const COLS: usize = 7;
     const BATCH_SIZE: usize = 2_000;
 
     let mut total_rows = 0u64;
@@ -29,7 +135,7 @@ pub async fn insert_top_scripts(top_scripts: &[TopScript], pool: &Pool<Postgres>
 
     for top_scripts_chunk in top_scripts.chunks(BATCH_SIZE) {
         let sql = format!(
-            "INSERT INTO top_scripts (timestamp, rank, script_public_key, script_public_key_address, amount)
+            "INSERT INTO top_scripts (timestamp, rank, script_public_key, script_public_key_address, amount, entity, category)
              VALUES {} ON CONFLICT DO NOTHING",
             generate_placeholders(top_scripts_chunk.len(), COLS)
         );
@@ -40,6 +146,41 @@ pub async fn insert_top_scripts(top_scripts: &[TopScript], pool: &Pool<Postgres>
             query = query.bind(&ts.script_public_key);
             query = query.bind(&ts.script_public_key_address);
             query = query.bind(ts.amount);
+            query = query.bind(&ts.entity);
+            query = query.bind(&ts.category);
+        }
+        total_rows += query.execute(&mut *tx).await?.rows_affected();
+    }
+    tx.commit().await?;
+    Ok(total_rows)
+}
+
+pub async fn insert_top_entities(top_entities: &[TopEntity], pool: &Pool<Postgres>) -> Result<u64, Error> {
+    if top_entities.is_empty() {
+        return Ok(0);
+    }
+    const COLS: usize = 8;
+    const BATCH_SIZE: usize = 2_000;
+
+    let mut total_rows = 0u64;
+    let mut tx = pool.begin().await?;
+
+    for top_entities_chunk in top_entities.chunks(BATCH_SIZE) {
+        let sql = format!(
+            "INSERT INTO top_entities (timestamp, rank, entity, category, script_public_key, script_public_key_address, script_count, amount)
+             VALUES {} ON CONFLICT DO NOTHING",
+            generate_placeholders(top_entities_chunk.len(), COLS)
+        );
+        let mut query = sqlx::query(&sql);
+        for te in top_entities_chunk {
+            query = query.bind(te.timestamp);
+            query = query.bind(te.rank);
+            query = query.bind(&te.entity);
+            query = query.bind(&te.category);
+            query = query.bind(&te.script_public_key);
+            query = query.bind(&te.script_public_key_address);
+            query = query.bind(te.script_count);
+            query = query.bind(te.amount);
         }
         total_rows += query.execute(&mut *tx).await?.rows_affected();
     }
@@ -47,6 +188,325 @@ pub async fn insert_top_scripts(top_scripts: &[TopScript], pool: &Pool<Postgres>
     Ok(total_rows)
 }
 
+pub async fn insert_entity_distribution_tiers(
+    entity_distribution_tiers: &[EntityDistributionTier],
+    pool: &Pool<Postgres>,
+) -> Result<u64, Error> {
+    if entity_distribution_tiers.is_empty() {
+        return Ok(0);
+    }
+    const COLS: usize = 8;
+    const BATCH_SIZE: usize = 2_000;
+
+    let mut total_rows = 0u64;
+    let mut tx = pool.begin().await?;
+
+    for tiers_chunk in entity_distribution_tiers.chunks(BATCH_SIZE) {
+        let sql = format!(
+            "INSERT INTO entity_distribution_tiers (timestamp, scheme, tier, lower_bound, upper_bound, count, amount, utxo_count)
+             VALUES {} ON CONFLICT DO NOTHING",
+            generate_placeholders(tiers_chunk.len(), COLS)
+        );
+        let mut query = sqlx::query(&sql);
+        for dt in tiers_chunk {
+            query = query.bind(dt.timestamp);
+            query = query.bind(&dt.scheme);
+            query = query.bind(dt.tier);
+            query = query.bind(dt.lower_bound);
+            query = query.bind(dt.upper_bound);
+            query = query.bind(dt.count);
+            query = query.bind(dt.amount);
+            query = query.bind(dt.utxo_count);
+        }
+        total_rows += query.execute(&mut *tx).await?.rows_affected();
+    }
+    tx.commit().await?;
+    Ok(total_rows)
+}
+
+pub async fn insert_top_script_changes(top_script_changes: &[TopScriptChange], pool: &Pool<Postgres>) -> Result<u64, Error> {
+    if top_script_changes.is_empty() {
+        return Ok(0);
+    }
+    const COLS: usize = 11;
+    const BATCH_SIZE: usize = 2_000;
+
+    let mut total_rows = 0u64;
+    let mut tx = pool.begin().await?;
+
+    for changes_chunk in top_script_changes.chunks(BATCH_SIZE) {
+        let sql = format!(
+            "INSERT INTO top_script_changes (timestamp, previous_timestamp, script_public_key, script_public_key_address, change,
+                rank, previous_rank, rank_change, amount, previous_amount, amount_change) VALUES {} ON CONFLICT DO NOTHING",
+            generate_placeholders(changes_chunk.len(), COLS)
+        );
+        let mut query = sqlx::query(&sql);
+        for tc in changes_chunk {
+            query = query.bind(tc.timestamp);
+            query = query.bind(tc.previous_timestamp);
+            query = query.bind(&tc.script_public_key);
+            query = query.bind(&tc.script_public_key_address);
+            query = query.bind(&tc.change);
+            query = query.bind(tc.rank);
+            query = query.bind(tc.previous_rank);
+            query = query.bind(tc.rank_change);
+            query = query.bind(tc.amount);
+            query = query.bind(tc.previous_amount);
+            query = query.bind(tc.amount_change);
+        }
+        total_rows += query.execute(&mut *tx).await?.rows_affected();
+    }
+    tx.commit().await?;
+    Ok(total_rows)
+}
+
+pub async fn insert_top_utxos(top_utxos: &[TopUtxo], pool: &Pool<Postgres>) -> Result<u64, Error> {
+    if top_utxos.is_empty() {
+        return Ok(0);
+    }
+    const COLS: usize = 9;
+    const BATCH_SIZE: usize = 2_000;
+
+    let mut total_rows = 0u64;
+    let mut tx = pool.begin().await?;
+
+    for top_utxos_chunk in top_utxos.chunks(BATCH_SIZE) {
+        let sql = format!(
+            "INSERT INTO top_utxos (timestamp, rank, transaction_id, index, amount, script_public_key, script_public_key_address,
+                block_daa_score, is_coinbase) VALUES {} ON CONFLICT DO NOTHING",
+            generate_placeholders(top_utxos_chunk.len(), COLS)
+        );
+        let mut query = sqlx::query(&sql);
+        for tu in top_utxos_chunk {
+            query = query.bind(tu.timestamp);
+            query = query.bind(tu.rank);
+            query = query.bind(&tu.transaction_id);
+            query = query.bind(tu.index);
+            query = query.bind(tu.amount);
+            query = query.bind(&tu.script_public_key);
+            query = query.bind(&tu.script_public_key_address);
+            query = query.bind(tu.block_daa_score);
+            query = query.bind(tu.is_coinbase);
+        }
+        total_rows += query.execute(&mut *tx).await?.rows_affected();
+    }
+    tx.commit().await?;
+    Ok(total_rows)
+}
+
+pub async fn insert_concentration_metrics(metrics: &ConcentrationMetrics, pool: &Pool<Postgres>) -> Result<u64, Error> {
+    const COLS: usize = 11;
+    let sql = format!(
+        "INSERT INTO concentration_metrics (timestamp, script_count, total_amount, gini, hhi, scripts_50_percent, scripts_90_percent,
+            p50, p90, p99, p999) VALUES {} ON CONFLICT DO NOTHING",
+        generate_placeholders(1, COLS)
+    );
+    let query = sqlx::query(&sql)
+        .bind(metrics.timestamp)
+        .bind(metrics.script_count)
+        .bind(metrics.total_amount)
+        .bind(metrics.gini)
+        .bind(metrics.hhi)
+        .bind(metrics.scripts_50_percent)
+        .bind(metrics.scripts_90_percent)
+        .bind(metrics.p50)
+        .bind(metrics.p90)
+        .bind(metrics.p99)
+        .bind(metrics.p999);
+    Ok(query.execute(pool).await?.rows_affected())
+}
+
+pub async fn insert_supply_reconciliation(reconciliation: &SupplyReconciliation, pool: &Pool<Postgres>) -> Result<u64, Error> {
+    const COLS: usize = 7;
+    let sql = format!(
+        "INSERT INTO supply_reconciliation (timestamp, virtual_daa_score, expected_supply, scanned_supply, difference,
+            difference_percent, suspicious) VALUES {} ON CONFLICT DO NOTHING",
+        generate_placeholders(1, COLS)
+    );
+    let query = sqlx::query(&sql)
+        .bind(reconciliation.timestamp)
+        .bind(reconciliation.virtual_daa_score)
+        .bind(reconciliation.expected_supply)
+        .bind(reconciliation.scanned_supply)
+        .bind(reconciliation.difference)
+        .bind(reconciliation.difference_percent)
+        .bind(reconciliation.suspicious);
+    Ok(query.execute(pool).await?.rows_affected())
+}
+
+pub async fn insert_quarantined_snapshot(snapshot: &QuarantinedSnapshot, pool: &Pool<Postgres>) -> Result<u64, Error> {
+    const COLS: usize = 6;
+    let sql = format!(
+        "INSERT INTO quarantined_snapshots (timestamp, virtual_daa_score, total_supply, script_count, top_10_replaced, reasons)
+            VALUES {} ON CONFLICT DO NOTHING",
+        generate_placeholders(1, COLS)
+    );
+    let query = sqlx::query(&sql)
+        .bind(snapshot.timestamp)
+        .bind(snapshot.virtual_daa_score)
+        .bind(snapshot.total_supply)
+        .bind(snapshot.script_count)
+        .bind(snapshot.top_10_replaced)
+        .bind(&snapshot.reasons);
+    Ok(query.execute(pool).await?.rows_affected())
+}
+
+pub async fn insert_dormant_supply(dormant_supply: &[DormantSupply], pool: &Pool<Postgres>) -> Result<u64, Error> {
+    if dormant_supply.is_empty() {
+        return Ok(0);
+    }
+    const COLS: usize = 9;
+    let sql = format!(
+        "INSERT INTO dormant_supply (timestamp, threshold, max_block_daa_score, script_count, utxo_count, amount, amount_sompi,
+            supply_percent, reawakened_amount) VALUES {} ON CONFLICT DO NOTHING",
+        generate_placeholders(dormant_supply.len(), COLS)
+    );
+    let mut query = sqlx::query(&sql);
+    for ds in dormant_supply {
+        query = query.bind(ds.timestamp);
+        query = query.bind(&ds.threshold);
+        query = query.bind(ds.max_block_daa_score);
+        query = query.bind(ds.script_count);
+        query = query.bind(ds.utxo_count);
+        query = query.bind(ds.amount);
+        query = query.bind(ds.amount_sompi);
+        query = query.bind(ds.supply_percent);
+        query = query.bind(ds.reawakened_amount);
+    }
+    Ok(query.execute(pool).await?.rows_affected())
+}
+
+pub async fn insert_top_dormant_scripts(top_dormant_scripts: &[TopDormantScript], pool: &Pool<Postgres>) -> Result<u64, Error> {
+    if top_dormant_scripts.is_empty() {
+        return Ok(0);
+    }
+    const COLS: usize = 7;
+    const BATCH_SIZE: usize = 2_000;
+
+    let mut total_rows = 0u64;
+    let mut tx = pool.begin().await?;
+
+    for top_dormant_scripts_chunk in top_dormant_scripts.chunks(BATCH_SIZE) {
+        let sql = format!(
+            "INSERT INTO top_dormant_scripts (timestamp, rank, script_public_key, script_public_key_address, dormant_amount, amount,
+                oldest_block_daa_score) VALUES {} ON CONFLICT DO NOTHING",
+            generate_placeholders(top_dormant_scripts_chunk.len(), COLS)
+        );
+        let mut query = sqlx::query(&sql);
+        for tds in top_dormant_scripts_chunk {
+            query = query.bind(tds.timestamp);
+            query = query.bind(tds.rank);
+            query = query.bind(&tds.script_public_key);
+            query = query.bind(&tds.script_public_key_address);
+            query = query.bind(tds.dormant_amount);
+            query = query.bind(tds.amount);
+            query = query.bind(tds.oldest_block_daa_score);
+        }
+        total_rows += query.execute(&mut *tx).await?.rows_affected();
+    }
+    tx.commit().await?;
+    Ok(total_rows)
+}
+
+pub async fn insert_skipped_run(skipped_run: &SkippedRun, pool: &Pool<Postgres>) -> Result<u64, Error> {
+    const COLS: usize = 5;
+    let sql = format!(
+        "INSERT INTO skipped_runs (timestamp, reason, virtual_daa_score, virtual_lag_seconds, pruning_point_age_seconds)
+            VALUES {} ON CONFLICT DO NOTHING",
+        generate_placeholders(1, COLS)
+    );
+    let query = sqlx::query(&sql)
+        .bind(skipped_run.timestamp)
+        .bind(&skipped_run.reason)
+        .bind(skipped_run.virtual_daa_score)
+        .bind(skipped_run.virtual_lag_seconds)
+        .bind(skipped_run.pruning_point_age_seconds);
+    Ok(query.execute(pool).await?.rows_affected())
+}
+
+pub async fn insert_snapshot_anchor(anchor: &SnapshotAnchor, pool: &Pool<Postgres>) -> Result<u64, Error> {
+    const COLS: usize = 5;
+    let sql = format!(
+        "INSERT INTO snapshot_anchors (timestamp, mode, block_hash, daa_score, target_daa_score) VALUES {} ON CONFLICT DO NOTHING",
+        generate_placeholders(1, COLS)
+    );
+    let query = sqlx::query(&sql)
+        .bind(anchor.timestamp)
+        .bind(&anchor.mode)
+        .bind(&anchor.block_hash)
+        .bind(anchor.daa_score)
+        .bind(anchor.target_daa_score);
+    Ok(query.execute(pool).await?.rows_affected())
+}
+
+pub async fn insert_lorenz_curve(lorenz_curve: &[LorenzPoint], pool: &Pool<Postgres>) -> Result<u64, Error> {
+    if lorenz_curve.is_empty() {
+        return Ok(0);
+    }
+    const COLS: usize = 3;
+    let sql = format!(
+        "INSERT INTO lorenz_curve (timestamp, population_share, supply_share) VALUES {} ON CONFLICT DO NOTHING",
+        generate_placeholders(lorenz_curve.len(), COLS)
+    );
+    let mut query = sqlx::query(&sql);
+    for lp in lorenz_curve {
+        query = query.bind(lp.timestamp);
+        query = query.bind(lp.population_share);
+        query = query.bind(lp.supply_share);
+    }
+    Ok(query.execute(pool).await?.rows_affected())
+}
+
+pub async fn insert_watchlist_balances(watchlist_balances: &[WatchlistBalance], pool: &Pool<Postgres>) -> Result<u64, Error> {
+    if watchlist_balances.is_empty() {
+        return Ok(0);
+    }
+    const COLS: usize = 5;
+    const BATCH_SIZE: usize = 2_000;
+
+    let mut total_rows = 0u64;
+    let mut tx = pool.begin().await?;
+
+    for balances_chunk in watchlist_balances.chunks(BATCH_SIZE) {
+        let sql = format!(
+            "INSERT INTO watchlist_balances (timestamp, address, script_public_key, amount, utxo_count) VALUES {} ON CONFLICT DO NOTHING",
+            generate_placeholders(balances_chunk.len(), COLS)
+        );
+        let mut query = sqlx::query(&sql);
+        for wb in balances_chunk {
+            query = query.bind(wb.timestamp);
+            query = query.bind(&wb.address);
+            query = query.bind(&wb.script_public_key);
+            query = query.bind(wb.amount);
+            query = query.bind(wb.utxo_count);
+        }
+        total_rows += query.execute(&mut *tx).await?.rows_affected();
+    }
+    tx.commit().await?;
+    Ok(total_rows)
+}
+
+pub async fn insert_excluded_balances(excluded_balances: &[ExcludedBalance], pool: &Pool<Postgres>) -> Result<u64, Error> {
+    if excluded_balances.is_empty() {
+        return Ok(0);
+    }
+    const COLS: usize = 6;
+    let sql = format!(
+        "INSERT INTO excluded_balances (timestamp, rule_type, rule, script_count, utxo_count, amount) VALUES {} ON CONFLICT DO NOTHING",
+        generate_placeholders(excluded_balances.len(), COLS)
+    );
+    let mut query = sqlx::query(&sql);
+    for eb in excluded_balances {
+        query = query.bind(eb.timestamp);
+        query = query.bind(&eb.rule_type);
+        query = query.bind(&eb.rule);
+        query = query.bind(eb.script_count);
+        query = query.bind(eb.utxo_count);
+        query = query.bind(eb.amount);
+    }
+    Ok(query.execute(pool).await?.rows_affected())
+}
+
 pub fn generate_placeholders(rows: usize, columns: usize) -> String {
     (0..rows).map(|i| format!("({})", (1..=columns).map(|c| format!("${}", c + i * columns)).join(", "))).join(", ")
 }
@@ -1,5 +1,7 @@
 use sqlx::{Error, Pool, Postgres, Row};
 
+use crate::models::top_script::TopScript;
+
 pub async fn select_var(key: &str, pool: &Pool<Postgres>) -> Result<String, Error> {
     sqlx::query("SELECT value FROM vars WHERE key = $1").bind(key).fetch_one(pool).await?.try_get(0)
 }
@@ -7,3 +9,56 @@ pub async fn select_var(key: &str, pool: &Pool<Postgres>) -> Result<String, Erro
 pub async fn select_last_distribution_tier(pool: &Pool<Postgres>) -> Result<Option<i64>, Error> {
     sqlx::query_scalar::<_, Option<i64>>("SELECT max(timestamp) FROM distribution_tiers WHERE tier = 0").fetch_one(pool).await
 }
+
+pub async fn select_top_scripts(timestamp: i64, pool: &Pool<Postgres>) -> Result<Vec<TopScript>, Error> {
+    let rows = sqlx::query_as::<_, (i64, i32, Vec<u8>, Option<String>, i64, Option<String>, Option<String>)>(
+        "SELECT timestamp, rank, script_public_key, script_public_key_address, amount, entity, category
+         FROM top_scripts WHERE timestamp = $1 ORDER BY rank",
+    )
+    .bind(timestamp)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(timestamp, rank, script_public_key, script_public_key_address, amount, entity, category)| TopScript {
+            timestamp,
+            rank,
+            script_public_key,
+            script_public_key_address,
+            amount,
+            entity,
+            category,
+        })
+        .collect())
+}
+
+pub async fn select_snapshot_totals(timestamp: i64, pool: &Pool<Postgres>) -> Result<Option<(i64, i64)>, Error> {
+    sqlx::query_as::<_, (i64, i64)>(
+        "SELECT s.scanned_supply, c.script_count
+         FROM supply_reconciliation s JOIN concentration_metrics c ON c.timestamp = s.timestamp
+         WHERE s.timestamp = $1",
+    )
+    .bind(timestamp)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn select_dormant_supply(timestamp: i64, pool: &Pool<Postgres>) -> Result<Vec<(String, i64, i64)>, Error> {
+    sqlx::query_as::<_, (String, i64, i64)>(
+        "SELECT threshold, max_block_daa_score, amount_sompi FROM dormant_supply WHERE timestamp = $1",
+    )
+    .bind(timestamp)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn select_snapshot_anchor(timestamp: i64, pool: &Pool<Postgres>) -> Result<Option<(String, Vec<u8>)>, Error> {
+    sqlx::query_as::<_, (String, Vec<u8>)>("SELECT mode, block_hash FROM snapshot_anchors WHERE timestamp = $1")
+        .bind(timestamp)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn select_watchlist(pool: &Pool<Postgres>) -> Result<Vec<String>, Error> {
+    sqlx::query_scalar::<_, String>("SELECT address FROM watchlist").fetch_all(pool).await
+}
@@ -5,7 +5,7 @@ use crate::models::script_utxo_count::ScriptUtxoCount;
 use crate::query::insert::generate_placeholders;
 
 pub async fn replace_script_utxo_counts(script_utxo_counts: &[ScriptUtxoCount], pool: &Pool<Postgres>) -> Result<u64, Error> {
-    const COLS: usize = 3;
+    const COLS: usize = 10;
     const BATCH_SIZE: usize = 2_000;
 
     let mut total_rows = 0u64;
@@ -15,7 +15,8 @@ pub async fn replace_script_utxo_counts(script_utxo_counts: &[ScriptUtxoCount],
 
     for chunk in script_utxo_counts.chunks(BATCH_SIZE) {
         let sql = format!(
-            "INSERT INTO script_utxo_counts (script_public_key, script_public_key_address, count) VALUES {}",
+            "INSERT INTO script_utxo_counts (script_public_key, script_public_key_address, count, amount, min_amount, max_amount,
+                median_amount, dust_count, oldest_block_daa_score, newest_block_daa_score) VALUES {}",
             generate_placeholders(chunk.len(), COLS)
         );
         let mut query = sqlx::query(&sql);
@@ -23,6 +24,13 @@ pub async fn replace_script_utxo_counts(script_utxo_counts: &[ScriptUtxoCount],
             query = query.bind(&sc.script_public_key);
             query = query.bind(&sc.script_public_key_address);
             query = query.bind(sc.count);
+            query = query.bind(sc.amount);
+            query = query.bind(sc.min_amount);
+            query = query.bind(sc.max_amount);
+            query = query.bind(sc.median_amount);
+            query = query.bind(sc.dust_count);
+            query = query.bind(sc.oldest_block_daa_score);
+            query = query.bind(sc.newest_block_daa_score);
         }
         total_rows += query.execute(&mut *tx).await?.rows_affected();
     }
@@ -0,0 +1,90 @@
+use crate::addresses::labels::Label;
+use kaspa_addresses::{Address, Prefix};
+use kaspa_consensus_core::tx::ScriptPublicKey;
+use kaspa_txscript::pay_to_address_script;
+use regex::Regex;
+use std::collections::HashMap;
+
+pub enum ExclusionRule {
+    Address(String),
+    ScriptPattern(Regex),
+    Category(String),
+}
+
+impl ExclusionRule {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ExclusionRule::Address(_) => "address",
+            ExclusionRule::ScriptPattern(_) => "script_pattern",
+            ExclusionRule::Category(_) => "category",
+        }
+    }
+
+    pub fn value(&self) -> String {
+        match self {
+            ExclusionRule::Address(address) => address.clone(),
+            ExclusionRule::ScriptPattern(pattern) => pattern.to_string(),
+            ExclusionRule::Category(category) => category.clone(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Exclusions {
+    pub rules: Vec<ExclusionRule>,
+    addresses: HashMap<ScriptPublicKey, usize>,
+}
+
+impl Exclusions {
+    pub fn new(addresses: &[String], script_patterns: &[String], categories: &[String], prefix: Prefix) -> Result<Self, String> {
+        let mut exclusions = Exclusions::default();
+        for address in addresses {
+            let a = Address::try_from(address.as_str()).map_err(|e| format!("Invalid exclusion address {address}: {e}"))?;
+            if a.prefix != prefix {
+                return Err(format!("Invalid exclusion address {address}, expected prefix {prefix}"));
+            }
+            exclusions.addresses.insert(pay_to_address_script(&a), exclusions.rules.len());
+            exclusions.rules.push(ExclusionRule::Address(a.to_string()));
+        }
+        for pattern in script_patterns {
+            let regex = Regex::new(pattern).map_err(|e| format!("Invalid exclusion script pattern {pattern}: {e}"))?;
+            exclusions.rules.push(ExclusionRule::ScriptPattern(regex));
+        }
+        for category in categories {
+            exclusions.rules.push(ExclusionRule::Category(category.clone()));
+        }
+        Ok(exclusions)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns the index of the first rule matching the script, script patterns are matched against the script hex
+    pub fn matches(&self, script: &ScriptPublicKey, label: Option<&Label>) -> Option<usize> {
+        if self.is_empty() {
+            return None;
+        }
+        if let Some(&idx) = self.addresses.get(script) {
+            return Some(idx);
+        }
+        let mut script_hex: Option<String> = None;
+        for (idx, rule) in self.rules.iter().enumerate() {
+            match rule {
+                ExclusionRule::Address(_) => {}
+                ExclusionRule::ScriptPattern(regex) => {
+                    let hex = script_hex.get_or_insert_with(|| faster_hex::hex_string(script.script()));
+                    if regex.is_match(hex) {
+                        return Some(idx);
+                    }
+                }
+                ExclusionRule::Category(category) => {
+                    if label.and_then(|l| l.category.as_ref()) == Some(category) {
+                        return Some(idx);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
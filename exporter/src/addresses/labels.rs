@@ -0,0 +1,59 @@
+use kaspa_addresses::{Address, Prefix};
+use kaspa_consensus_core::tx::ScriptPublicKey;
+use kaspa_txscript::pay_to_address_script;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::fs;
+
+#[derive(Clone, Debug)]
+pub struct Label {
+    pub entity: String,
+    pub category: Option<String>,
+}
+
+/// Reads labels from a csv file with lines in the format `address,entity[,category]`.
+/// Empty lines and lines starting with '#' are ignored. An entity takes the first category given for it in the file.
+pub fn read_labels_file(path: &str, prefix: Prefix) -> std::io::Result<HashMap<ScriptPublicKey, Label>> {
+    let mut labels = HashMap::new();
+    let mut categories: HashMap<String, String> = HashMap::new();
+    for line in fs::read_to_string(path)?.lines().map(|line| line.trim()).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        let mut fields = line.split(',').map(|field| field.trim());
+        let (Some(address), Some(entity)) = (fields.next(), fields.next().filter(|e| !e.is_empty())) else {
+            warn!("Ignoring invalid label line: {line}");
+            continue;
+        };
+        let mut category = fields.next().filter(|c| !c.is_empty()).map(|c| c.to_string());
+        if let Some(c) = category.as_mut() {
+            let first = categories.entry(entity.to_string()).or_insert_with(|| c.clone());
+            if first != c {
+                warn!("Ignoring category {c} of {address}, {entity} is already labeled {first}");
+                c.clone_from(first);
+            }
+        }
+        match Address::try_from(address) {
+            Ok(a) if a.prefix != prefix => warn!("Ignoring label for {address}, expected prefix {prefix}"),
+            Ok(a) => {
+                labels.insert(pay_to_address_script(&a), Label { entity: entity.to_string(), category });
+            }
+            Err(e) => warn!("Ignoring label for invalid address {address}: {e}"),
+        }
+    }
+    Ok(labels)
+}
+
+/// Loads the labels file if given, a file that can't be read leaves all scripts unlabeled
+pub fn load_labels(labels_file: Option<String>, prefix: Prefix) -> HashMap<ScriptPublicKey, Label> {
+    let Some(labels_file) = labels_file else {
+        return HashMap::new();
+    };
+    match read_labels_file(&labels_file, prefix) {
+        Ok(labels) => {
+            info!("Loaded {} address labels", labels.len());
+            labels
+        }
+        Err(e) => {
+            warn!("Failed to read labels file {labels_file}: {e}");
+            HashMap::new()
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod exclusions;
+pub mod labels;
+pub mod watchlist;
@@ -0,0 +1,56 @@
+use kaspa_addresses::{Address, Prefix};
+use kaspa_consensus_core::tx::ScriptPublicKey;
+use kaspa_txscript::pay_to_address_script;
+use log::{info, warn};
+use simply_kaspa_utxo_exporter_database::client::KaspaDbClient;
+use std::fs;
+
+/// Reads addresses from a file, one per line. Empty lines and lines starting with '#' are ignored
+pub fn read_watchlist_file(path: &str) -> std::io::Result<Vec<String>> {
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.to_string())
+        .collect())
+}
+
+/// Parses addresses into (address, script public key) pairs, skipping invalid ones and addresses for other networks
+pub fn parse_addresses(addresses: Vec<String>, prefix: Prefix) -> Vec<(String, ScriptPublicKey)> {
+    let mut watchlist: Vec<(String, ScriptPublicKey)> = vec![];
+    for address in addresses {
+        match Address::try_from(address.as_str()) {
+            Ok(a) if a.prefix != prefix => warn!("Ignoring watchlist address {address}, expected prefix {prefix}"),
+            Ok(a) => {
+                let address = a.to_string();
+                if !watchlist.iter().any(|(existing, _)| *existing == address) {
+                    watchlist.push((address, pay_to_address_script(&a)));
+                }
+            }
+            Err(e) => warn!("Ignoring invalid watchlist address {address}: {e}"),
+        }
+    }
+    watchlist
+}
+
+/// Loads the watchlist from the watchlist file and the watchlist table of each database
+pub async fn load_watchlist(watchlist_file: Option<String>, dbs: &[KaspaDbClient], prefix: Prefix) -> Vec<(String, ScriptPublicKey)> {
+    let mut addresses = vec![];
+    if let Some(watchlist_file) = watchlist_file {
+        match read_watchlist_file(&watchlist_file) {
+            Ok(file_addresses) => addresses.extend(file_addresses),
+            Err(e) => warn!("Failed to read watchlist file {watchlist_file}: {e}"),
+        }
+    }
+    for db in dbs {
+        match db.select_watchlist().await {
+            Ok(db_addresses) => addresses.extend(db_addresses),
+            Err(e) => warn!("Failed to read watchlist from {}: {e}", db.url_cleaned),
+        }
+    }
+    let watchlist = parse_addresses(addresses, prefix);
+    if !watchlist.is_empty() {
+        info!("Loaded {} watchlist addresses", watchlist.len());
+    }
+    watchlist
+}
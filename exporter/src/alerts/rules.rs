@@ -0,0 +1,163 @@
+use kaspa_addresses::Prefix;
+use kaspa_consensus_core::tx::ScriptPublicKey;
+use kaspa_txscript::extract_script_pub_key_address;
+use serde::Serialize;
+use simply_kaspa_utxo_exporter_database::models::top_script_change::TopScriptChange;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Alert {
+    TopScriptChange {
+        timestamp: i64,
+        script_public_key: String,
+        address: Option<String>,
+        rank: Option<i32>,
+        previous_rank: Option<i32>,
+        amount: i64,
+        amount_change: i64,
+    },
+    TopScriptEntered {
+        timestamp: i64,
+        script_public_key: String,
+        address: Option<String>,
+        rank: i32,
+        amount: i64,
+    },
+    DormantSpent {
+        timestamp: i64,
+        script_public_key: String,
+        address: Option<String>,
+        dormant_amount: i64,
+        spent_amount: i64,
+    },
+}
+
+impl Alert {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Alert::TopScriptChange { .. } => "top_script_change",
+            Alert::TopScriptEntered { .. } => "top_script_entered",
+            Alert::DormantSpent { .. } => "dormant_spent",
+        }
+    }
+
+    pub fn timestamp(&self) -> i64 {
+        match self {
+            Alert::TopScriptChange { timestamp, .. }
+            | Alert::TopScriptEntered { timestamp, .. }
+            | Alert::DormantSpent { timestamp, .. } => *timestamp,
+        }
+    }
+
+    pub fn script_public_key(&self) -> &str {
+        match self {
+            Alert::TopScriptChange { script_public_key, .. }
+            | Alert::TopScriptEntered { script_public_key, .. }
+            | Alert::DormantSpent { script_public_key, .. } => script_public_key,
+        }
+    }
+}
+
+pub struct AlertRules {
+    /// Only scripts ranked within this (1-based) rank are considered for balance change alerts
+    pub top_change_rank: i32,
+    /// Minimum absolute balance change, in the same unit as the top scripts amount
+    pub top_change_amount: Option<i64>,
+    /// Alert when a new script enters within this (1-based) rank
+    pub entered_rank: Option<i32>,
+    /// Minimum amount of dormant coins spent from a single script, in sompi
+    pub dormant_spent_amount: Option<u64>,
+}
+
+impl AlertRules {
+    pub fn is_empty(&self) -> bool {
+        self.top_change_amount.is_none() && self.entered_rank.is_none() && self.dormant_spent_amount.is_none()
+    }
+}
+
+pub fn top_script_alerts(rules: &AlertRules, changes: &[TopScriptChange]) -> Vec<Alert> {
+    let mut alerts = vec![];
+    for change in changes {
+        let within_rank = |rank: i32| change.rank.or(change.previous_rank).is_some_and(|r| r < rank);
+        if let (Some(threshold), Some(amount_change)) = (rules.top_change_amount, change.amount_change) {
+            if within_rank(rules.top_change_rank) && amount_change.abs() >= threshold {
+                alerts.push(Alert::TopScriptChange {
+                    timestamp: change.timestamp,
+                    script_public_key: faster_hex::hex_string(&change.script_public_key),
+                    address: change.script_public_key_address.clone(),
+                    rank: change.rank,
+                    previous_rank: change.previous_rank,
+                    amount: change.amount,
+                    amount_change,
+                });
+            }
+        }
+        if let (Some(entered_rank), None, Some(rank)) = (rules.entered_rank, change.previous_rank, change.rank) {
+            if rank < entered_rank {
+                alerts.push(Alert::TopScriptEntered {
+                    timestamp: change.timestamp,
+                    script_public_key: faster_hex::hex_string(&change.script_public_key),
+                    address: change.script_public_key_address.clone(),
+                    rank,
+                    amount: change.amount,
+                });
+            }
+        }
+    }
+    alerts
+}
+
+/// Compares per script dormant amounts (in sompi) with the previous snapshot
+pub fn dormant_alerts(
+    rules: &AlertRules,
+    timestamp: i64,
+    previous: &HashMap<ScriptPublicKey, u64>,
+    current: &HashMap<ScriptPublicKey, u64>,
+    prefix: Prefix,
+    convert: impl Fn(u64) -> i64,
+) -> Vec<Alert> {
+    let Some(threshold) = rules.dormant_spent_amount else {
+        return vec![];
+    };
+    let mut alerts = vec![];
+    for (script, &dormant_amount) in previous {
+        let spent_amount = dormant_amount.saturating_sub(current.get(script).copied().unwrap_or(0));
+        if spent_amount >= threshold {
+            alerts.push(Alert::DormantSpent {
+                timestamp,
+                script_public_key: faster_hex::hex_string(script.script()),
+                address: extract_script_pub_key_address(script, prefix).ok().map(|a| a.payload_to_string()),
+                dormant_amount: convert(dormant_amount),
+                spent_amount: convert(spent_amount),
+            });
+        }
+    }
+    alerts
+}
+
+/// Suppresses alerts repeating for the same rule and script
+#[derive(Default)]
+pub struct AlertCooldown {
+    last_alerts: HashMap<(&'static str, String), i64>,
+}
+
+impl AlertCooldown {
+    /// Drops alerts which already fired for the same rule and script within `cooldown_ms`, or earlier in the same run
+    pub fn filter(&mut self, alerts: Vec<Alert>, cooldown_ms: i64) -> Vec<Alert> {
+        let cooldown_ms = cooldown_ms.max(1);
+        let mut filtered = Vec::with_capacity(alerts.len());
+        for alert in alerts {
+            let key = (alert.kind(), alert.script_public_key().to_string());
+            if self.last_alerts.get(&key).is_some_and(|&last| alert.timestamp() - last < cooldown_ms) {
+                continue;
+            }
+            self.last_alerts.insert(key, alert.timestamp());
+            filtered.push(alert);
+        }
+        if let Some(latest) = self.last_alerts.values().max().copied() {
+            self.last_alerts.retain(|_, last| latest - *last < cooldown_ms);
+        }
+        filtered
+    }
+}
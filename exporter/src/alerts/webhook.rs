@@ -0,0 +1,140 @@
+use crate::alerts::rules::Alert;
+use log::{debug, error, info, warn};
+use serde_json::json;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+
+/// Number of runs whose alerts can wait for delivery
+const QUEUE_SIZE: usize = 16;
+
+/// Delivers alerts from a background task, so unreachable webhooks don't hold up the runs
+pub struct WebhookNotifier {
+    sender: mpsc::Sender<Vec<Alert>>,
+    worker: JoinHandle<()>,
+    delivery: Arc<Delivery>,
+}
+
+struct Delivery {
+    client: reqwest::Client,
+    urls: Vec<String>,
+    network: String,
+    retry_count: u16,
+    retry_interval: Duration,
+    dead_letter_file: Option<String>,
+    run: Arc<AtomicBool>,
+}
+
+impl WebhookNotifier {
+    /// Must be called within a tokio runtime, retries stop once `run` is cleared
+    pub fn new(
+        urls: Vec<String>,
+        network: String,
+        retry_count: u16,
+        retry_interval: Duration,
+        dead_letter_file: Option<String>,
+        run: Arc<AtomicBool>,
+    ) -> Result<Self, reqwest::Error> {
+        let client = reqwest::Client::builder().timeout(Duration::from_secs(10)).build()?;
+        let delivery = Arc::new(Delivery { client, urls, network, retry_count, retry_interval, dead_letter_file, run });
+        let (sender, mut receiver) = mpsc::channel::<Vec<Alert>>(QUEUE_SIZE);
+        let worker = tokio::spawn({
+            let delivery = delivery.clone();
+            async move {
+                while let Some(alerts) = receiver.recv().await {
+                    delivery.deliver(&alerts).await;
+                }
+            }
+        });
+        Ok(WebhookNotifier { sender, worker, delivery })
+    }
+
+    /// Queues the alerts for delivery, they are written to the dead letter log if the queue is full
+    pub fn notify(&self, alerts: Vec<Alert>) {
+        if alerts.is_empty() {
+            return;
+        }
+        if let Err(e) = self.sender.try_send(alerts) {
+            let (alerts, reason) = match e {
+                TrySendError::Full(alerts) => (alerts, "delivery queue is full"),
+                TrySendError::Closed(alerts) => (alerts, "delivery has stopped"),
+            };
+            warn!("Unable to queue {} alerts, {reason}", alerts.len());
+            for alert in alerts.iter() {
+                let payload = self.delivery.payload(alert);
+                self.delivery.urls.iter().for_each(|url| self.delivery.dead_letter(url, &payload, reason));
+            }
+        }
+    }
+
+    /// Waits until the queued alerts have been delivered or dead-lettered
+    pub async fn close(self) {
+        drop(self.sender);
+        if let Err(e) = self.worker.await {
+            error!("Webhook delivery failed: {e}");
+        }
+    }
+}
+
+impl Delivery {
+    fn payload(&self, alert: &Alert) -> serde_json::Value {
+        json!({ "network": self.network, "alert": alert })
+    }
+
+    /// Posts each alert to every webhook url, alerts which can't be delivered are written to the dead letter log
+    async fn deliver(&self, alerts: &[Alert]) {
+        for alert in alerts {
+            let payload = self.payload(alert);
+            for url in self.urls.iter() {
+                if let Err(e) = self.post_with_retry(url, &payload).await {
+                    self.dead_letter(url, &payload, &e);
+                }
+            }
+        }
+        info!("Delivered {} alerts to {} webhooks", alerts.len(), self.urls.len());
+    }
+
+    async fn post_with_retry(&self, url: &str, payload: &serde_json::Value) -> Result<(), String> {
+        let mut last_error = String::new();
+        for retry in 0..=self.retry_count {
+            if retry > 0 {
+                sleep(self.retry_interval).await;
+            }
+            match self.client.post(url).json(payload).send().await.and_then(|r| r.error_for_status()) {
+                Ok(_) => {
+                    debug!("Delivered alert to {url}");
+                    return Ok(());
+                }
+                Err(e) => {
+                    warn!("Failed to deliver alert to {url}, retry {retry}/{}: {e}", self.retry_count);
+                    last_error = e.to_string();
+                }
+            }
+            if !self.run.load(Ordering::Relaxed) {
+                break;
+            }
+        }
+        Err(last_error)
+    }
+
+    fn dead_letter(&self, url: &str, payload: &serde_json::Value, error: &str) {
+        let entry = json!({ "url": url, "error": error, "payload": payload });
+        let Some(dead_letter_file) = &self.dead_letter_file else {
+            error!("Undeliverable alert: {entry}");
+            return;
+        };
+        match OpenOptions::new().create(true).append(true).open(dead_letter_file) {
+            Ok(mut file) => {
+                if let Err(e) = writeln!(file, "{entry}") {
+                    error!("Failed to write to dead letter file {dead_letter_file}: {e}, undeliverable alert: {entry}");
+                }
+            }
+            Err(e) => error!("Failed to open dead letter file {dead_letter_file}: {e}, undeliverable alert: {entry}"),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use simply_kaspa_utxo_exporter_database::models::top_script::TopScript;
+use std::collections::HashSet;
+
+const TOP_RANKS: usize = 10;
+
+/// Plausibility limits for a new snapshot compared to the previous committed one, zero disables a check.
+pub struct GuardrailLimits {
+    pub supply_drop_percent: f64,
+    pub script_count_drop_percent: f64,
+    pub top_10_replaced: usize,
+}
+
+pub struct SnapshotStats<'a> {
+    pub total_supply: i64,
+    pub script_count: i64,
+    pub top_scripts: &'a [TopScript],
+}
+
+pub struct GuardrailReport {
+    pub top_10_replaced: usize,
+    pub violations: Vec<String>,
+}
+
+/// Checks the current snapshot against the previous one, checks lacking previous data are skipped.
+pub fn check(limits: &GuardrailLimits, previous: &SnapshotStats, current: &SnapshotStats) -> GuardrailReport {
+    let mut violations = vec![];
+    if limits.supply_drop_percent > 0.0 && previous.total_supply > 0 {
+        let drop = drop_percent(previous.total_supply, current.total_supply);
+        if drop > limits.supply_drop_percent {
+            violations.push(format!(
+                "total supply dropped {drop:.2}% ({} -> {}), limit is {}%",
+                previous.total_supply, current.total_supply, limits.supply_drop_percent
+            ));
+        }
+    }
+    if limits.script_count_drop_percent > 0.0 && previous.script_count > 0 {
+        let drop = drop_percent(previous.script_count, current.script_count);
+        if drop > limits.script_count_drop_percent {
+            violations.push(format!(
+                "script count dropped {drop:.2}% ({} -> {}), limit is {}%",
+                previous.script_count, current.script_count, limits.script_count_drop_percent
+            ));
+        }
+    }
+    let mut top_10_replaced = 0;
+    if !previous.top_scripts.is_empty() {
+        let previous_top: HashSet<&[u8]> =
+            previous.top_scripts.iter().take(TOP_RANKS).map(|ts| ts.script_public_key.as_slice()).collect();
+        top_10_replaced =
+            current.top_scripts.iter().take(TOP_RANKS).filter(|ts| !previous_top.contains(ts.script_public_key.as_slice())).count();
+        if limits.top_10_replaced > 0 && top_10_replaced > limits.top_10_replaced {
+            violations
+                .push(format!("{top_10_replaced} of the top {TOP_RANKS} scripts were replaced, limit is {}", limits.top_10_replaced));
+        }
+    }
+    GuardrailReport { top_10_replaced, violations }
+}
+
+fn drop_percent(previous: i64, current: i64) -> f64 {
+    (previous - current) as f64 / previous as f64 * 100.0
+}
@@ -0,0 +1,3 @@
+pub mod guardrails;
+pub mod tiers;
+pub mod top_scripts;
@@ -0,0 +1,40 @@
+use simply_kaspa_utxo_exporter_scan::tier_scheme::TierScheme;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// Tier used for scripts missing from either snapshot
+pub const NO_TIER: i16 = -1;
+
+#[derive(Clone, Default)]
+pub struct Migration {
+    pub count: u64,
+    pub amount: u64,
+    pub previous_amount: u64,
+}
+
+/// Builds a sparse (from tier, to tier) transition matrix per scheme between two sets of script balances
+pub fn migrations<K: Hash + Eq>(
+    schemes: &[TierScheme],
+    previous: &HashMap<K, (u64, u64)>,
+    current: &HashMap<K, (u64, u64)>,
+) -> Vec<BTreeMap<(i16, i16), Migration>> {
+    let mut matrices = vec![BTreeMap::<(i16, i16), Migration>::new(); schemes.len()];
+    for (script, &(amount, _)) in current {
+        let previous_amount = previous.get(script).map(|&(a, _)| a);
+        for (scheme, matrix) in schemes.iter().zip(matrices.iter_mut()) {
+            let from = previous_amount.map(|a| scheme.tier(a) as i16).unwrap_or(NO_TIER);
+            let migration = matrix.entry((from, scheme.tier(amount) as i16)).or_default();
+            migration.count += 1;
+            migration.amount += amount;
+            migration.previous_amount += previous_amount.unwrap_or(0);
+        }
+    }
+    for (_, &(previous_amount, _)) in previous.iter().filter(|(script, _)| !current.contains_key(*script)) {
+        for (scheme, matrix) in schemes.iter().zip(matrices.iter_mut()) {
+            let migration = matrix.entry((scheme.tier(previous_amount) as i16, NO_TIER)).or_default();
+            migration.count += 1;
+            migration.previous_amount += previous_amount;
+        }
+    }
+    matrices
+}
@@ -0,0 +1,56 @@
+use simply_kaspa_utxo_exporter_database::models::top_script::TopScript;
+use simply_kaspa_utxo_exporter_database::models::top_script_change::TopScriptChange;
+use std::collections::HashMap;
+
+pub const ENTERED: &str = "entered";
+pub const EXITED: &str = "exited";
+pub const RETAINED: &str = "retained";
+
+/// Compares the current top scripts with the previous snapshot.
+/// `current_amounts` holds the current balance of previous top scripts, scripts missing from it are considered emptied.
+pub fn compare(
+    timestamp: i64,
+    previous: &[TopScript],
+    current: &[TopScript],
+    current_amounts: &HashMap<Vec<u8>, i64>,
+) -> Vec<TopScriptChange> {
+    let Some(previous_timestamp) = previous.first().map(|ts| ts.timestamp) else {
+        return vec![];
+    };
+    let previous_by_script: HashMap<&[u8], &TopScript> = previous.iter().map(|ts| (ts.script_public_key.as_slice(), ts)).collect();
+    let mut changes = Vec::with_capacity(current.len());
+    for ts in current {
+        let prev = previous_by_script.get(ts.script_public_key.as_slice());
+        changes.push(TopScriptChange {
+            timestamp,
+            previous_timestamp,
+            script_public_key: ts.script_public_key.clone(),
+            script_public_key_address: ts.script_public_key_address.clone(),
+            change: if prev.is_some() { RETAINED } else { ENTERED }.to_string(),
+            rank: Some(ts.rank),
+            previous_rank: prev.map(|p| p.rank),
+            rank_change: prev.map(|p| p.rank - ts.rank),
+            amount: ts.amount,
+            previous_amount: prev.map(|p| p.amount),
+            amount_change: prev.map(|p| ts.amount - p.amount),
+        });
+    }
+    let current_scripts: HashMap<&[u8], &TopScript> = current.iter().map(|ts| (ts.script_public_key.as_slice(), ts)).collect();
+    for prev in previous.iter().filter(|p| !current_scripts.contains_key(p.script_public_key.as_slice())) {
+        let amount = current_amounts.get(&prev.script_public_key).copied().unwrap_or(0);
+        changes.push(TopScriptChange {
+            timestamp,
+            previous_timestamp,
+            script_public_key: prev.script_public_key.clone(),
+            script_public_key_address: prev.script_public_key_address.clone(),
+            change: EXITED.to_string(),
+            rank: None,
+            previous_rank: Some(prev.rank),
+            rank_change: None,
+            amount,
+            previous_amount: Some(prev.amount),
+            amount_change: Some(amount - prev.amount),
+        });
+    }
+    changes
+}
@@ -0,0 +1,2 @@
+pub mod multi_consensus;
+pub mod sync;
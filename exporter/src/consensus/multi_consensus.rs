@@ -0,0 +1,103 @@
+use kaspa_consensus_core::network::NetworkId;
+use kaspa_database::registry::DatabaseStorePrefixes;
+use log::{info, warn};
+use regex::Regex;
+use rocksdb::{DBWithThreadMode, MultiThreaded};
+use serde::Deserialize;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Leading fields of kaspad's MultiConsensusMetadata, bincode ignores the trailing ones
+#[derive(Deserialize)]
+struct MultiConsensusMetadata {
+    current_consensus_key: Option<u64>,
+    staging_consensus_key: Option<u64>,
+}
+
+/// Leading fields of kaspad's ConsensusEntry
+#[derive(Deserialize)]
+struct ConsensusEntry {
+    _key: u64,
+    directory_name: String,
+}
+
+pub struct ConsensusStatus {
+    pub active: Option<String>,
+    pub staging: Option<String>,
+}
+
+/// Reads the active and staging consensus directory names from kaspad's meta db.
+pub fn read_consensus_status(meta_db_path: &Path) -> Result<ConsensusStatus, Box<dyn Error>> {
+    let mut opts = rocksdb::Options::default();
+    opts.create_if_missing(false);
+    let db = DBWithThreadMode::<MultiThreaded>::open_for_read_only(&opts, meta_db_path, false)?;
+
+    let metadata_key: Vec<u8> = DatabaseStorePrefixes::MultiConsensusMetadata.into();
+    let metadata_bytes = db.get(metadata_key)?.ok_or("Multi-consensus metadata not found")?;
+    let metadata = parse_metadata(&metadata_bytes)?;
+
+    let directory_name = |key: Option<u64>| -> Result<Option<String>, Box<dyn Error>> {
+        let Some(key) = key else {
+            return Ok(None);
+        };
+        let mut entry_key: Vec<u8> = DatabaseStorePrefixes::ConsensusEntries.into();
+        entry_key.extend_from_slice(&key.to_le_bytes());
+        match db.get(entry_key)? {
+            Some(bytes) => Ok(Some(parse_entry(&bytes)?)),
+            // kaspad names consensus directories after their key
+            None => Ok(Some(format!("consensus-{key:0>3}"))),
+        }
+    };
+    Ok(ConsensusStatus {
+        active: directory_name(metadata.current_consensus_key)?,
+        staging: directory_name(metadata.staging_consensus_key)?,
+    })
+}
+
+/// Locates the consensus directory to read, the active one unless `consensus_dir` is given.
+pub fn get_db_path(base_dir: String, consensus_dir: Option<String>, network_id: NetworkId) -> Result<PathBuf, Box<dyn Error>> {
+    let datadir_path = PathBuf::from(base_dir).join(network_id.to_prefixed()).join("datadir");
+    let consensus_base_path = datadir_path.join("consensus");
+
+    if let Some(consensus_dir) = consensus_dir {
+        let consensus_path = consensus_base_path.join(consensus_dir);
+        info!("Using specified consensus directory: {}", consensus_path.display());
+        return Ok(consensus_path);
+    }
+    let meta_path = datadir_path.join("meta");
+    if meta_path.exists() {
+        let status = read_consensus_status(&meta_path)?;
+        if let Some(staging) = status.staging.as_ref() {
+            match status.active.as_ref() {
+                Some(active) => info!("kaspad is syncing staging consensus {staging}, using active consensus {active}"),
+                None => return Err(format!("kaspad is in IBD, refusing to read staging consensus {staging}").into()),
+            }
+        }
+        let active = status.active.ok_or("No active consensus found in kaspad metadata")?;
+        let consensus_path = consensus_base_path.join(active);
+        info!("Using active consensus directory: {}", consensus_path.display());
+        Ok(consensus_path)
+    } else {
+        warn!("kaspad metadata not found in {}, falling back to the highest numbered consensus", meta_path.display());
+        let re = Regex::new(r"^consensus-(\d+)$").unwrap();
+        let consensus_dir = fs::read_dir(&consensus_base_path)?
+            .filter_map(|e| e.ok())
+            .filter_map(|e| e.file_name().into_string().ok())
+            .filter_map(|name| re.captures(&name).and_then(|c| c[1].parse::<u64>().ok()).map(|n| (n, name.clone())))
+            .max_by_key(|(n, _)| *n)
+            .map(|(_, name)| name)
+            .ok_or("No matching consensus directory found")?;
+        let consensus_path = consensus_base_path.join(consensus_dir);
+        info!("Using auto-detected consensus directory: {}", consensus_path.display());
+        Ok(consensus_path)
+    }
+}
+
+fn parse_metadata(bytes: &[u8]) -> Result<MultiConsensusMetadata, bincode::Error> {
+    bincode::deserialize(bytes)
+}
+
+fn parse_entry(bytes: &[u8]) -> Result<String, bincode::Error> {
+    bincode::deserialize::<ConsensusEntry>(bytes).map(|e| e.directory_name)
+}
@@ -0,0 +1,67 @@
+use std::error::Error;
+use std::fmt;
+
+/// Max lag of the node's sync indicators behind the wall clock, zero disables a check.
+pub struct SyncLimits {
+    pub max_virtual_lag_ms: u64,
+    pub max_pruning_point_age_ms: u64,
+}
+
+pub struct SyncIndicators {
+    pub now_ms: u64,
+    pub virtual_daa_score: u64,
+    pub virtual_past_median_time_ms: u64,
+    pub pruning_point_timestamp_ms: u64,
+    pub pruning_point_daa_score: u64,
+    /// Pruning point DAA score seen by the previous committed run, zero if unknown
+    pub previous_pruning_point_daa_score: u64,
+}
+
+impl SyncIndicators {
+    pub fn virtual_lag_ms(&self) -> u64 {
+        self.now_ms.saturating_sub(self.virtual_past_median_time_ms)
+    }
+
+    pub fn pruning_point_age_ms(&self) -> u64 {
+        self.now_ms.saturating_sub(self.pruning_point_timestamp_ms)
+    }
+}
+
+/// Returned in place of a scan when the node is not synced.
+#[derive(Debug)]
+pub struct RunSkipped {
+    pub reason: String,
+    pub virtual_daa_score: u64,
+    pub virtual_lag_ms: u64,
+    pub pruning_point_age_ms: u64,
+}
+
+impl fmt::Display for RunSkipped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.reason)
+    }
+}
+
+impl Error for RunSkipped {}
+
+pub fn check(limits: &SyncLimits, indicators: &SyncIndicators) -> Result<(), RunSkipped> {
+    let virtual_lag_ms = indicators.virtual_lag_ms();
+    let pruning_point_age_ms = indicators.pruning_point_age_ms();
+    let reason = if limits.max_virtual_lag_ms > 0 && virtual_lag_ms > limits.max_virtual_lag_ms {
+        format!("virtual past median time is {}s behind, limit is {}s", virtual_lag_ms / 1000, limits.max_virtual_lag_ms / 1000)
+    } else if limits.max_pruning_point_age_ms > 0 && pruning_point_age_ms > limits.max_pruning_point_age_ms {
+        format!(
+            "pruning point is {}h old, limit is {}h",
+            pruning_point_age_ms / 3_600_000,
+            limits.max_pruning_point_age_ms / 3_600_000
+        )
+    } else if indicators.pruning_point_daa_score < indicators.previous_pruning_point_daa_score {
+        format!(
+            "pruning point moved backwards from DAA score {} to {}",
+            indicators.previous_pruning_point_daa_score, indicators.pruning_point_daa_score
+        )
+    } else {
+        return Ok(());
+    };
+    Err(RunSkipped { reason, virtual_daa_score: indicators.virtual_daa_score, virtual_lag_ms, pruning_point_age_ms })
+}
@@ -0,0 +1,110 @@
+use crate::control::state::Control;
+use log::{info, warn};
+use serde_json::json;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::timeout;
+
+const MAX_REQUEST_SIZE: usize = 8192;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Serves POST /run, /reload and /cancel, requests must carry an `Authorization: Bearer <token>` header
+pub async fn serve(listener: TcpListener, token: String, control: Arc<Control>) {
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                warn!("Control endpoint failed to accept a connection: {e}");
+                continue;
+            }
+        };
+        let token = token.clone();
+        let control = control.clone();
+        tokio::spawn(async move {
+            if let Err(e) = respond(stream, &token, &control).await {
+                warn!("Control endpoint failed to respond to {peer}: {e}");
+            }
+        });
+    }
+}
+
+async fn respond(mut stream: TcpStream, token: &str, control: &Control) -> io::Result<()> {
+    let (status, body) = match timeout(REQUEST_TIMEOUT, read_head(&mut stream)).await {
+        Ok(Ok(head)) => handle(control, token, &head),
+        Ok(Err(e)) => return Err(e),
+        Err(_) => (408, json!({"error": "request timeout"}).to_string()),
+    };
+    let response = format!(
+        "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        reason(status),
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Reads the request line and headers, the body is ignored
+async fn read_head(stream: &mut TcpStream) -> io::Result<String> {
+    let mut head = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < MAX_REQUEST_SIZE {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&chunk[..n]);
+    }
+    Ok(String::from_utf8_lossy(&head).into_owned())
+}
+
+/// Handles the request head, returns the status code and JSON body
+pub fn handle(control: &Control, token: &str, request: &str) -> (u16, String) {
+    let mut lines = request.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (method, path) = (request_line.next().unwrap_or_default(), request_line.next().unwrap_or_default());
+    let authorized = lines.take_while(|line| !line.is_empty()).filter_map(|line| line.split_once(':')).any(|(name, value)| {
+        name.trim().eq_ignore_ascii_case("authorization")
+            && value.trim().strip_prefix("Bearer ").is_some_and(|t| constant_time_eq(t.trim().as_bytes(), token.as_bytes()))
+    });
+    let (status, body) = if !authorized {
+        (401, json!({"error": "unauthorized"}))
+    } else {
+        match path {
+            "/run" | "/reload" | "/cancel" if method != "POST" => (405, json!({"error": "method not allowed"})),
+            "/run" => {
+                control.request_run();
+                (202, json!({"status": "run requested"}))
+            }
+            "/reload" => {
+                control.request_reload();
+                (202, json!({"status": "reload requested"}))
+            }
+            "/cancel" => match control.cancel_scan() {
+                true => (202, json!({"status": "scan cancelled"})),
+                false => (409, json!({"error": "no scan in progress"})),
+            },
+            _ => (404, json!({"error": "not found"})),
+        }
+    };
+    info!("Control endpoint: {method} {path} -> {status}");
+    (status, body.to_string())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        202 => "Accepted",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        _ => "",
+    }
+}
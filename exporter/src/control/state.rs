@@ -0,0 +1,54 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Requests from signals and the control endpoint, picked up by the main loop
+#[derive(Default)]
+pub struct Control {
+    run_requested: AtomicBool,
+    reload_requested: AtomicBool,
+    scan: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl Control {
+    pub fn request_run(&self) {
+        self.run_requested.store(true, Ordering::Relaxed);
+    }
+
+    pub fn take_run_request(&self) -> bool {
+        self.run_requested.swap(false, Ordering::Relaxed)
+    }
+
+    pub fn request_reload(&self) {
+        self.reload_requested.store(true, Ordering::Relaxed);
+    }
+
+    pub fn take_reload_request(&self) -> bool {
+        self.reload_requested.swap(false, Ordering::Relaxed)
+    }
+
+    /// Returns the run flag for a new scan, cleared when the scan is cancelled
+    pub fn start_scan(&self) -> Arc<AtomicBool> {
+        let run = Arc::new(AtomicBool::new(true));
+        *self.scan.lock().unwrap() = Some(run.clone());
+        run
+    }
+
+    pub fn finish_scan(&self) {
+        *self.scan.lock().unwrap() = None;
+    }
+
+    pub fn is_scanning(&self) -> bool {
+        self.scan.lock().unwrap().is_some()
+    }
+
+    /// Returns false if no scan is in progress
+    pub fn cancel_scan(&self) -> bool {
+        match self.scan.lock().unwrap().as_ref() {
+            Some(run) => {
+                run.store(false, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+}
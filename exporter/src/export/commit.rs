@@ -0,0 +1,150 @@
+use crate::consensus::sync::RunSkipped;
+use crate::export::results::ExportResults;
+use log::{debug, error, info};
+use simply_kaspa_utxo_exporter_database::client::KaspaDbClient;
+use simply_kaspa_utxo_exporter_database::models::quarantined_snapshot::QuarantinedSnapshot;
+use simply_kaspa_utxo_exporter_database::models::skipped_run::SkippedRun;
+use simply_kaspa_utxo_exporter_database::models::watchlist_balance::WatchlistBalance;
+use std::error::Error;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+pub async fn commit_to_db_with_retry(
+    run: Arc<AtomicBool>,
+    db_retry_count: u16,
+    db_retry_interval: u64,
+    dbs: Vec<KaspaDbClient>,
+    results: &ExportResults,
+) {
+    for db in dbs {
+        debug!(
+            "Committing {} tiers, {} top scripts and {} script utxo counts to {}",
+            results.tiers.len(),
+            results.top_scripts.len(),
+            results.script_utxo_counts.len(),
+            db.url_cleaned
+        );
+        for retry in 0..=db_retry_count {
+            match commit_to_db(&db, results).await {
+                Ok(()) => {
+                    info!(
+                        "Committed {} tiers, {} top scripts and {} script utxo counts to {}",
+                        results.tiers.len(),
+                        results.top_scripts.len(),
+                        results.script_utxo_counts.len(),
+                        db.url_cleaned
+                    );
+                    break;
+                }
+                Err(e) => {
+                    let start_sleep_time = Instant::now();
+                    error!("Failed to commit results to {}, retry {retry}/{db_retry_count}: {e}", db.url_cleaned);
+                    while start_sleep_time.elapsed() < Duration::from_secs(db_retry_interval) {
+                        if !run.load(Ordering::Relaxed) {
+                            return;
+                        }
+                        sleep(Duration::from_secs(3)).await;
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub async fn quarantine_with_retry(
+    run: Arc<AtomicBool>,
+    db_retry_count: u16,
+    db_retry_interval: u64,
+    dbs: Vec<KaspaDbClient>,
+    quarantined: &QuarantinedSnapshot,
+) {
+    for db in dbs {
+        for retry in 0..=db_retry_count {
+            match db.insert_quarantined_snapshot(quarantined).await {
+                Ok(_) => break,
+                Err(e) => {
+                    let start_sleep_time = Instant::now();
+                    error!("Failed to quarantine snapshot to {}, retry {retry}/{db_retry_count}: {e}", db.url_cleaned);
+                    while start_sleep_time.elapsed() < Duration::from_secs(db_retry_interval) {
+                        if !run.load(Ordering::Relaxed) {
+                            return;
+                        }
+                        sleep(Duration::from_secs(3)).await;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns false unless the balances were committed to every database
+pub async fn commit_watchlist_balances_with_retry(
+    run: Arc<AtomicBool>,
+    db_retry_count: u16,
+    db_retry_interval: u64,
+    dbs: &[KaspaDbClient],
+    watchlist_balances: &[WatchlistBalance],
+) -> bool {
+    let mut committed = true;
+    for db in dbs {
+        for retry in 0..=db_retry_count {
+            match db.insert_watchlist_balances(watchlist_balances).await {
+                Ok(_) => break,
+                Err(e) => {
+                    error!("Failed to commit watchlist balances to {}, retry {retry}/{db_retry_count}: {e}", db.url_cleaned);
+                    if retry == db_retry_count {
+                        committed = false;
+                        break;
+                    }
+                    let start_sleep_time = Instant::now();
+                    while start_sleep_time.elapsed() < Duration::from_secs(db_retry_interval) {
+                        if !run.load(Ordering::Relaxed) {
+                            return false;
+                        }
+                        sleep(Duration::from_secs(3)).await;
+                    }
+                }
+            }
+        }
+    }
+    committed
+}
+
+pub async fn commit_to_db(db: &KaspaDbClient, results: &ExportResults) -> Result<(), Box<dyn Error>> {
+    db.insert_distribution_tiers(&results.tiers).await?;
+    db.insert_tier_migrations(&results.tier_migrations).await?;
+    db.insert_utxo_distribution_tiers(&results.utxo_tiers).await?;
+    db.insert_top_scripts(&results.top_scripts).await?;
+    db.insert_top_script_changes(&results.top_script_changes).await?;
+    db.insert_top_entities(&results.top_entities).await?;
+    db.insert_entity_distribution_tiers(&results.entity_tiers).await?;
+    db.insert_top_utxos(&results.top_utxos).await?;
+    db.replace_script_utxo_counts(&results.script_utxo_counts).await?;
+    db.insert_concentration_metrics(&results.concentration_metrics).await?;
+    db.insert_lorenz_curve(&results.lorenz_curve).await?;
+    db.insert_watchlist_balances(&results.watchlist_balances).await?;
+    db.insert_excluded_balances(&results.excluded_balances).await?;
+    db.insert_supply_reconciliation(&results.supply_reconciliation).await?;
+    db.insert_snapshot_anchor(&results.snapshot_anchor).await?;
+    db.insert_dormant_supply(&results.dormant_supply).await?;
+    db.insert_top_dormant_scripts(&results.top_dormant_scripts).await?;
+    Ok(())
+}
+
+/// Records a skipped run to every database, failures are only logged
+pub async fn record_skipped_run(dbs: &[KaspaDbClient], timestamp: i64, skipped: &RunSkipped) {
+    let skipped_run = SkippedRun {
+        timestamp,
+        reason: skipped.reason.clone(),
+        virtual_daa_score: skipped.virtual_daa_score as i64,
+        virtual_lag_seconds: (skipped.virtual_lag_ms / 1000) as i64,
+        pruning_point_age_seconds: (skipped.pruning_point_age_ms / 1000) as i64,
+    };
+    for db in dbs.iter() {
+        if let Err(e) = db.insert_skipped_run(&skipped_run).await {
+            error!("Failed to record skipped run to {}: {e}", db.url_cleaned);
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod commit;
+pub mod results;
+pub mod rpc_balances;
+pub mod scan;
+pub mod settings;
@@ -0,0 +1,502 @@
+use crate::alerts::rules::{dormant_alerts, top_script_alerts, Alert};
+use crate::comparison;
+use crate::comparison::guardrails::SnapshotStats;
+use crate::export::scan::RunContext;
+use crate::metrics::{concentration, lorenz, supply};
+use crate::snapshot::source::ScanSummary;
+use kaspa_addresses::Prefix;
+use kaspa_consensus_core::config::ConfigBuilder;
+use kaspa_consensus_core::constants::SOMPI_PER_KASPA;
+use kaspa_consensus_core::network::NetworkId;
+use kaspa_consensus_core::tx::ScriptPublicKey;
+use kaspa_txscript::extract_script_pub_key_address;
+use log::{info, warn};
+use simply_kaspa_utxo_exporter_cli::cli_args::CliArgs;
+use simply_kaspa_utxo_exporter_database::models::concentration_metrics::ConcentrationMetrics;
+use simply_kaspa_utxo_exporter_database::models::distribution_tier::DistributionTier;
+use simply_kaspa_utxo_exporter_database::models::dormant_supply::DormantSupply;
+use simply_kaspa_utxo_exporter_database::models::entity_distribution_tier::EntityDistributionTier;
+use simply_kaspa_utxo_exporter_database::models::excluded_balance::ExcludedBalance;
+use simply_kaspa_utxo_exporter_database::models::lorenz_point::LorenzPoint;
+use simply_kaspa_utxo_exporter_database::models::script_utxo_count::ScriptUtxoCount;
+use simply_kaspa_utxo_exporter_database::models::snapshot_anchor::SnapshotAnchor;
+use simply_kaspa_utxo_exporter_database::models::supply_reconciliation::SupplyReconciliation;
+use simply_kaspa_utxo_exporter_database::models::tier_migration::TierMigration;
+use simply_kaspa_utxo_exporter_database::models::top_dormant_script::TopDormantScript;
+use simply_kaspa_utxo_exporter_database::models::top_entity::TopEntity;
+use simply_kaspa_utxo_exporter_database::models::top_script::TopScript;
+use simply_kaspa_utxo_exporter_database::models::top_script_change::TopScriptChange;
+use simply_kaspa_utxo_exporter_database::models::top_utxo::TopUtxo;
+use simply_kaspa_utxo_exporter_database::models::utxo_distribution_tier::UtxoDistributionTier;
+use simply_kaspa_utxo_exporter_database::models::watchlist_balance::WatchlistBalance;
+use simply_kaspa_utxo_exporter_scan::aggregate::{self, Aggregation};
+use simply_kaspa_utxo_exporter_scan::metrics::tiers::Tiers;
+use simply_kaspa_utxo_exporter_scan::scanner::ScanResult;
+use std::collections::HashMap;
+
+/// The rows of a run to commit, plus what the next run compares against
+pub struct ExportResults {
+    pub tiers: Vec<DistributionTier>,
+    pub utxo_tiers: Vec<UtxoDistributionTier>,
+    pub tier_migrations: Vec<TierMigration>,
+    pub entity_tiers: Vec<EntityDistributionTier>,
+    pub top_scripts: Vec<TopScript>,
+    pub top_script_changes: Vec<TopScriptChange>,
+    pub top_entities: Vec<TopEntity>,
+    pub top_utxos: Vec<TopUtxo>,
+    pub script_utxo_counts: Vec<ScriptUtxoCount>,
+    pub concentration_metrics: ConcentrationMetrics,
+    pub lorenz_curve: Vec<LorenzPoint>,
+    pub watchlist_balances: Vec<WatchlistBalance>,
+    pub excluded_balances: Vec<ExcludedBalance>,
+    pub supply_reconciliation: SupplyReconciliation,
+    pub snapshot_anchor: SnapshotAnchor,
+    pub dormant_supply: Vec<DormantSupply>,
+    pub top_dormant_scripts: Vec<TopDormantScript>,
+    pub dormancy_totals: HashMap<String, (u64, u64)>,
+    pub pruning_point_daa_score: u64,
+    pub script_amounts: Option<HashMap<ScriptPublicKey, (u64, u64)>>,
+    pub dormant_amounts: HashMap<ScriptPublicKey, u64>,
+    pub alerts: Vec<Alert>,
+}
+
+/// The last committed snapshot, as far as the next run needs it
+#[derive(Default)]
+pub struct PreviousSnapshot {
+    pub timestamp: i64,
+    pub top_scripts: Vec<TopScript>,
+    pub script_amounts: Option<HashMap<ScriptPublicKey, (u64, u64)>>,
+    pub dormant_amounts: HashMap<ScriptPublicKey, u64>,
+    pub total_supply: i64,
+    pub script_count: i64,
+    pub dormancy_totals: HashMap<String, (u64, u64)>,
+    pub pruning_point_daa_score: u64,
+    pub anchor_hash: Vec<u8>,
+}
+
+impl PreviousSnapshot {
+    pub fn stats(&self) -> SnapshotStats<'_> {
+        SnapshotStats { total_supply: self.total_supply, script_count: self.script_count, top_scripts: &self.top_scripts }
+    }
+}
+
+impl ExportResults {
+    pub fn stats(&self) -> SnapshotStats<'_> {
+        SnapshotStats {
+            total_supply: self.supply_reconciliation.scanned_supply,
+            script_count: self.concentration_metrics.script_count,
+            top_scripts: &self.top_scripts,
+        }
+    }
+}
+
+/// Converts the scan and aggregation into the rows to commit
+pub fn export_results(
+    cli_args: &CliArgs,
+    context: &RunContext,
+    scan: ScanResult,
+    summary: ScanSummary,
+    aggregation: Aggregation,
+) -> ExportResults {
+    let RunContext { network_id, start_time_ms, previous, watchlist, labels, settings, snapshot_mode } = *context;
+    let prefix = Prefix::from(network_id);
+    let ScanResult {
+        total_amount,
+        script_amounts,
+        utxo_tiers,
+        top_utxos,
+        watched_dust,
+        mut dormant_amounts,
+        dormancy,
+        script_utxos,
+        ..
+    } = scan;
+    let Aggregation { tiers, top_scripts, balances, excluded, entity_tiers, top_entities } = aggregation;
+    let convert = |amount: u64| if cli_args.amount_in_sompi { amount } else { amount / SOMPI_PER_KASPA } as i64;
+    let convert_fractional =
+        |amount: u64| if cli_args.amount_in_sompi { amount as f64 } else { amount as f64 / SOMPI_PER_KASPA as f64 };
+    let previous_scripts_amounts: HashMap<Vec<u8>, i64> = previous
+        .top_scripts
+        .iter()
+        .filter_map(|ts| {
+            let (amount, _) = script_amounts.get(&ScriptPublicKey::from_vec(0, ts.script_public_key.clone()))?;
+            Some((ts.script_public_key.clone(), convert(*amount)))
+        })
+        .collect();
+    let script_utxo_counts = script_utxos
+        .into_iter()
+        .map(|(script, utxos)| {
+            let stats = utxos.stats();
+            ScriptUtxoCount {
+                script_public_key_address: cli_args
+                    .extract_addresses
+                    .then(|| extract_script_pub_key_address(&script, prefix).ok().map(|a| a.payload_to_string()))
+                    .flatten(),
+                script_public_key: script.script().to_vec(),
+                count: stats.count as i64,
+                amount: convert(stats.amount),
+                min_amount: convert(stats.min_amount),
+                max_amount: convert(stats.max_amount),
+                median_amount: convert(stats.median_amount),
+                dust_count: stats.dust_count as i64,
+                oldest_block_daa_score: stats.oldest_block_daa_score as i64,
+                newest_block_daa_score: stats.newest_block_daa_score as i64,
+            }
+        })
+        .collect();
+
+    let mut distribution_tiers = vec![];
+    for Tiers { scheme, tiers } in tiers {
+        for (idx, tier) in tiers.into_iter().enumerate() {
+            let amount_kas = tier.amount / SOMPI_PER_KASPA;
+            let (lower_bound, upper_bound) = scheme.bounds(idx);
+            info!("Tier {}/{idx}, count: {}, utxos: {}, total: {amount_kas} KAS", scheme.name, tier.count, tier.utxo_count);
+            distribution_tiers.push(DistributionTier {
+                tier: idx as i16,
+                timestamp: start_time_ms,
+                scheme: scheme.name.clone(),
+                lower_bound: lower_bound as i64,
+                upper_bound: upper_bound.map(|b| b as i64),
+                count: tier.count as i64,
+                amount: amount_kas as i64,
+                utxo_count: tier.utxo_count as i64,
+                avg_utxo_amount: if tier.utxo_count > 0 {
+                    tier.amount as f64 / tier.utxo_count as f64 / SOMPI_PER_KASPA as f64
+                } else {
+                    0.0
+                },
+                avg_utxos_per_script: if tier.count > 0 { tier.utxo_count as f64 / tier.count as f64 } else { 0.0 },
+            });
+        }
+    }
+
+    let mut tier_migrations = vec![];
+    if let Some(previous_script_amounts) = previous.script_amounts.as_ref() {
+        let matrices = comparison::tiers::migrations(&cli_args.tier_schemes, previous_script_amounts, &script_amounts);
+        for (scheme, matrix) in cli_args.tier_schemes.iter().zip(matrices) {
+            for ((from_tier, to_tier), migration) in matrix {
+                tier_migrations.push(TierMigration {
+                    timestamp: start_time_ms,
+                    previous_timestamp: previous.timestamp,
+                    scheme: scheme.name.clone(),
+                    from_tier,
+                    to_tier,
+                    count: migration.count as i64,
+                    amount: (migration.amount / SOMPI_PER_KASPA) as i64,
+                    previous_amount: (migration.previous_amount / SOMPI_PER_KASPA) as i64,
+                });
+            }
+        }
+        info!("Calculated {} tier migrations since last run", tier_migrations.len());
+    }
+
+    let mut utxo_distribution_tiers = vec![];
+    for Tiers { scheme, tiers } in utxo_tiers {
+        for (idx, tier) in tiers.into_iter().enumerate() {
+            let (lower_bound, upper_bound) = scheme.bounds(idx);
+            utxo_distribution_tiers.push(UtxoDistributionTier {
+                tier: idx as i16,
+                timestamp: start_time_ms,
+                scheme: scheme.name.clone(),
+                lower_bound: lower_bound as i64,
+                upper_bound: upper_bound.map(|b| b as i64),
+                count: tier.count as i64,
+                amount: (tier.amount / SOMPI_PER_KASPA) as i64,
+            });
+        }
+    }
+
+    let top_scripts = top_scripts
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (amount, spk))| {
+            let amount_kas = amount / SOMPI_PER_KASPA;
+            let label = labels.get(&ScriptPublicKey::from_vec(0, spk.clone()));
+            if idx < 10 {
+                match extract_script_pub_key_address(&ScriptPublicKey::from_vec(0, spk.clone()), prefix) {
+                    Ok(address) => info!("Top {} address: {address}, total: {amount_kas} KAS", idx + 1),
+                    Err(_) => info!("Top {} script: {}, total: {amount_kas} KAS", idx + 1, faster_hex::hex_string(&spk)),
+                }
+            }
+            TopScript {
+                rank: idx as i32,
+                timestamp: start_time_ms,
+                script_public_key: spk.clone(),
+                script_public_key_address: cli_args
+                    .extract_addresses
+                    .then(|| ScriptPublicKey::from_vec(0, spk))
+                    .and_then(|spk| extract_script_pub_key_address(&spk, prefix).ok())
+                    .map(|a| a.payload_to_string()),
+                amount: if cli_args.amount_in_sompi { amount } else { amount_kas } as i64,
+                entity: label.map(|l| l.entity.clone()),
+                category: label.and_then(|l| l.category.clone()),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut entity_tiers_rows = vec![];
+    let top_entities = top_entities
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (amount, top_entity))| {
+            let (entity, category, script_public_key, script_count) = match top_entity {
+                aggregate::TopEntity::Entity(name, balance) => (Some(name), balance.category, None, balance.script_count),
+                aggregate::TopEntity::Script(script) => (None, None, Some(script), 1),
+            };
+            if idx < 10 {
+                let name = entity.as_deref().unwrap_or("(unlabeled)");
+                info!("Top {} entity: {name}, total: {} KAS", idx + 1, amount / SOMPI_PER_KASPA);
+            }
+            TopEntity {
+                timestamp: start_time_ms,
+                rank: idx as i32,
+                entity,
+                category,
+                script_public_key_address: script_public_key
+                    .as_ref()
+                    .filter(|_| cli_args.extract_addresses)
+                    .and_then(|spk| extract_script_pub_key_address(&ScriptPublicKey::from_vec(0, spk.clone()), prefix).ok())
+                    .map(|a| a.payload_to_string()),
+                script_public_key,
+                script_count: script_count as i64,
+                amount: convert(amount),
+            }
+        })
+        .collect();
+    for Tiers { scheme, tiers } in entity_tiers {
+        for (idx, tier) in tiers.into_iter().enumerate() {
+            let (lower_bound, upper_bound) = scheme.bounds(idx);
+            entity_tiers_rows.push(EntityDistributionTier {
+                tier: idx as i16,
+                timestamp: start_time_ms,
+                scheme: scheme.name.clone(),
+                lower_bound: lower_bound as i64,
+                upper_bound: upper_bound.map(|b| b as i64),
+                count: tier.count as i64,
+                amount: (tier.amount / SOMPI_PER_KASPA) as i64,
+                utxo_count: tier.utxo_count as i64,
+            });
+        }
+    }
+
+    let top_script_changes =
+        comparison::top_scripts::compare(start_time_ms, &previous.top_scripts, &top_scripts, &previous_scripts_amounts);
+    if let Some(inflow) = top_script_changes.iter().filter(|c| c.amount_change.unwrap_or(0) > 0).max_by_key(|c| c.amount_change) {
+        info!("Biggest top script inflow: {}, rank {:?}", inflow.amount_change.unwrap(), inflow.rank.map(|r| r + 1));
+    }
+    if let Some(outflow) = top_script_changes.iter().filter(|c| c.amount_change.unwrap_or(0) < 0).min_by_key(|c| c.amount_change) {
+        info!(
+            "Biggest top script outflow: {}, previous rank {:?}",
+            outflow.amount_change.unwrap(),
+            outflow.previous_rank.map(|r| r + 1)
+        );
+    }
+
+    let mut alerts = top_script_alerts(&settings.alert_rules, &top_script_changes);
+    if let Some(dormant_spent_amount) = settings.alert_rules.dormant_spent_amount {
+        alerts.extend(dormant_alerts(
+            &settings.alert_rules,
+            start_time_ms,
+            &previous.dormant_amounts,
+            &dormant_amounts,
+            prefix,
+            convert,
+        ));
+        dormant_amounts.retain(|_, amount| *amount >= dormant_spent_amount);
+    }
+    if !alerts.is_empty() {
+        info!("Triggered {} alerts", alerts.len());
+    }
+
+    let top_utxos = top_utxos
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (amount, transaction_id, index, block_daa_score, is_coinbase, version, script))| TopUtxo {
+            rank: idx as i32,
+            timestamp: start_time_ms,
+            transaction_id: transaction_id.to_vec(),
+            index: index as i64,
+            amount: if cli_args.amount_in_sompi { amount } else { amount / SOMPI_PER_KASPA } as i64,
+            script_public_key_address: cli_args
+                .extract_addresses
+                .then(|| extract_script_pub_key_address(&ScriptPublicKey::from_vec(version, script.clone()), prefix).ok())
+                .flatten()
+                .map(|a| a.payload_to_string()),
+            script_public_key: script,
+            block_daa_score: block_daa_score as i64,
+            is_coinbase,
+        })
+        .collect();
+
+    let metrics = concentration::calculate(&balances);
+    info!(
+        "Gini: {:.4}, HHI: {:.6}, scripts controlling 50%: {}, 90%: {}",
+        metrics.gini, metrics.hhi, metrics.scripts_50_percent, metrics.scripts_90_percent
+    );
+    let concentration_metrics = ConcentrationMetrics {
+        timestamp: start_time_ms,
+        script_count: balances.len() as i64,
+        total_amount: convert(balances.iter().sum()),
+        gini: metrics.gini,
+        hhi: metrics.hhi,
+        scripts_50_percent: metrics.scripts_50_percent as i64,
+        scripts_90_percent: metrics.scripts_90_percent as i64,
+        p50: convert_fractional(metrics.p50),
+        p90: convert_fractional(metrics.p90),
+        p99: convert_fractional(metrics.p99),
+        p999: convert_fractional(metrics.p999),
+    };
+
+    let watchlist_balances = watchlist
+        .iter()
+        .map(|(address, spk)| {
+            let (amount, utxo_count) = script_amounts.get(spk).copied().unwrap_or_default();
+            let (dust_amount, dust_count) = watched_dust.get(spk).copied().unwrap_or_default();
+            WatchlistBalance {
+                timestamp: start_time_ms,
+                address: address.clone(),
+                script_public_key: spk.script().to_vec(),
+                amount: convert(amount + dust_amount),
+                utxo_count: (utxo_count + dust_count) as i64,
+            }
+        })
+        .collect();
+
+    let excluded_balances = settings
+        .exclusions
+        .rules
+        .iter()
+        .zip(excluded)
+        .map(|(rule, excluded)| {
+            info!(
+                "Excluded {} {}, scripts: {}, total: {} KAS",
+                rule.kind(),
+                rule.value(),
+                excluded.script_count,
+                excluded.amount / SOMPI_PER_KASPA
+            );
+            ExcludedBalance {
+                timestamp: start_time_ms,
+                rule_type: rule.kind().to_string(),
+                rule: rule.value(),
+                script_count: excluded.script_count as i64,
+                utxo_count: excluded.utxo_count as i64,
+                amount: convert(excluded.amount),
+            }
+        })
+        .collect();
+
+    let expected_supply = expected_supply(network_id, summary.daa_score);
+    let difference = total_amount as i128 - expected_supply as i128;
+    let difference_percent = if expected_supply > 0 { difference as f64 * 100.0 / expected_supply as f64 } else { 0.0 };
+    let suspicious = difference_percent.abs() > cli_args.supply_tolerance_percent;
+    if suspicious {
+        warn!(
+            "Scanned supply {} KAS differs {difference_percent:.4}% from expected supply {} KAS at DAA score {}",
+            total_amount / SOMPI_PER_KASPA,
+            expected_supply / SOMPI_PER_KASPA as u128,
+            summary.daa_score
+        );
+    } else {
+        info!(
+            "Scanned supply differs {difference_percent:.4}% from expected supply {} KAS",
+            expected_supply / SOMPI_PER_KASPA as u128
+        );
+    }
+    let supply_reconciliation = SupplyReconciliation {
+        timestamp: start_time_ms,
+        virtual_daa_score: summary.daa_score as i64,
+        expected_supply: convert(expected_supply as u64),
+        scanned_supply: convert(total_amount),
+        difference: if cli_args.amount_in_sompi { difference } else { difference / SOMPI_PER_KASPA as i128 } as i64,
+        difference_percent,
+        suspicious,
+    };
+
+    let snapshot_anchor = SnapshotAnchor {
+        timestamp: start_time_ms,
+        mode: summary.anchor_mode,
+        block_hash: summary.anchor_hash,
+        daa_score: summary.daa_score as i64,
+        target_daa_score: snapshot_mode.target_daa_score().map(|t| t as i64),
+    };
+
+    let mut dormancy_totals = HashMap::with_capacity(dormancy.thresholds.len());
+    let dormant_supply = dormancy
+        .thresholds
+        .iter()
+        .map(|threshold| {
+            let supply_percent = if total_amount > 0 { threshold.amount as f64 * 100.0 / total_amount as f64 } else { 0.0 };
+            let reawakened_amount = threshold.reawakened_amount();
+            info!(
+                "Dormant supply {}: {} KAS ({supply_percent:.2}%), reawakened: {} KAS",
+                threshold.name,
+                threshold.amount / SOMPI_PER_KASPA,
+                reawakened_amount.map(|a| (a / SOMPI_PER_KASPA).to_string()).unwrap_or_else(|| "-".to_string())
+            );
+            dormancy_totals.insert(threshold.name.clone(), (threshold.max_block_daa_score, threshold.amount));
+            DormantSupply {
+                timestamp: start_time_ms,
+                threshold: threshold.name.clone(),
+                max_block_daa_score: threshold.max_block_daa_score as i64,
+                script_count: dormancy.script_count(threshold) as i64,
+                utxo_count: threshold.utxo_count as i64,
+                amount: convert(threshold.amount),
+                amount_sompi: threshold.amount as i64,
+                supply_percent,
+                reawakened_amount: reawakened_amount.map(convert),
+            }
+        })
+        .collect();
+    let top_dormant_scripts = dormancy
+        .largest(cli_args.top_dormant_scripts_count as usize)
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (script, dormant_amount, oldest_block_daa_score))| TopDormantScript {
+            timestamp: start_time_ms,
+            rank: idx as i32,
+            script_public_key: script.script().to_vec(),
+            script_public_key_address: cli_args
+                .extract_addresses
+                .then(|| extract_script_pub_key_address(script, prefix).ok().map(|a| a.payload_to_string()))
+                .flatten(),
+            dormant_amount: convert(dormant_amount),
+            amount: convert(script_amounts.get(script).map(|(amount, _)| *amount).unwrap_or_default()),
+            oldest_block_daa_score: oldest_block_daa_score as i64,
+        })
+        .collect();
+
+    let lorenz_curve = lorenz::sample(&balances, &cli_args.lorenz_points)
+        .into_iter()
+        .map(|(population_share, supply_share)| LorenzPoint { timestamp: start_time_ms, population_share, supply_share })
+        .collect();
+
+    ExportResults {
+        tiers: distribution_tiers,
+        utxo_tiers: utxo_distribution_tiers,
+        tier_migrations,
+        entity_tiers: entity_tiers_rows,
+        top_scripts,
+        top_script_changes,
+        top_entities,
+        top_utxos,
+        script_utxo_counts,
+        concentration_metrics,
+        lorenz_curve,
+        watchlist_balances,
+        excluded_balances,
+        supply_reconciliation,
+        snapshot_anchor,
+        dormant_supply,
+        top_dormant_scripts,
+        dormancy_totals,
+        pruning_point_daa_score: summary.pruning_point_daa_score,
+        script_amounts: cli_args.tier_migrations.then_some(script_amounts),
+        dormant_amounts,
+        alerts,
+    }
+}
+
+fn expected_supply(network_id: NetworkId, virtual_daa_score: u64) -> u128 {
+    supply::network_supply(&ConfigBuilder::new(network_id.into()).build().params, virtual_daa_score)
+}
@@ -0,0 +1,123 @@
+use crate::addresses::watchlist::load_watchlist;
+use crate::consensus::sync::RunSkipped;
+use crate::control::state::Control;
+use crate::export::commit::{commit_watchlist_balances_with_retry, record_skipped_run};
+use crate::export::settings::{reload, RunSettings};
+use crate::rpc::node::address_balances;
+use crate::rpc::wrpc::WrpcNode;
+use crate::schedule::scheduler::Scheduler;
+use chrono::{Timelike, Utc};
+use kaspa_addresses::Prefix;
+use kaspa_consensus_core::constants::SOMPI_PER_KASPA;
+use kaspa_consensus_core::network::NetworkId;
+use kaspa_consensus_core::tx::ScriptPublicKey;
+use log::{error, info, warn};
+use simply_kaspa_utxo_exporter_cli::cli_args::CliArgs;
+use simply_kaspa_utxo_exporter_database::client::KaspaDbClient;
+use simply_kaspa_utxo_exporter_database::models::watchlist_balance::WatchlistBalance;
+use std::error::Error;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// Runs the schedule on a node's RPC instead of the consensus database, only exporting the watchlist balances
+pub async fn export_rpc_balances(
+    run: Arc<AtomicBool>,
+    control: Arc<Control>,
+    mut cli_args: CliArgs,
+    mut settings: RunSettings,
+    network_id: NetworkId,
+    rpc_url: &str,
+    dbs: &[KaspaDbClient],
+    mut scheduler: Scheduler,
+) {
+    info!("Reading watchlist balances from {rpc_url}, run schedule: {}, next run at {}", scheduler.schedule(), scheduler.next_run());
+    let mut run_requested = false;
+    while run.load(Ordering::Relaxed) {
+        if control.take_reload_request() {
+            reload(&run, &mut cli_args, &mut settings, &mut scheduler, network_id);
+        }
+        let now = Utc::now().with_nanosecond(0).unwrap();
+        run_requested |= control.take_run_request();
+        if let Some(start_time) = scheduler.due(now).or(run_requested.then_some(now)) {
+            let start_time_ms = start_time.timestamp_millis();
+            let watchlist = load_watchlist(cli_args.watchlist_file.clone(), dbs, Prefix::from(network_id)).await;
+            match read_rpc_watchlist_balances(rpc_url, network_id, &watchlist, start_time_ms, cli_args.amount_in_sompi).await {
+                Ok(watchlist_balances) => {
+                    let committed = commit_watchlist_balances_with_retry(
+                        run.clone(),
+                        cli_args.db_retry_count,
+                        cli_args.db_retry_interval,
+                        dbs,
+                        &watchlist_balances,
+                    )
+                    .await;
+                    if committed {
+                        run_requested = false;
+                        scheduler.completed(start_time, Utc::now());
+                        info!("Committed {} watchlist balances, next run at {}", watchlist_balances.len(), scheduler.next_run());
+                    } else if run.load(Ordering::Relaxed) {
+                        error!(
+                            "Failed to commit watchlist balances, retrying the run in {} seconds",
+                            cli_args.data_dir_retry_interval
+                        );
+                        if cli_args.data_dir_retry_interval > 3 {
+                            sleep(Duration::from_secs(cli_args.data_dir_retry_interval - 3)).await;
+                        }
+                    }
+                }
+                Err(e) => {
+                    if let Some(skipped) = e.downcast_ref::<RunSkipped>() {
+                        warn!(
+                            "Node is not synced, skipping run and retrying in {} seconds: {skipped}",
+                            cli_args.data_dir_retry_interval
+                        );
+                        record_skipped_run(dbs, start_time_ms, skipped).await;
+                    } else {
+                        error!(
+                            "Failed to read watchlist balances from {rpc_url}, retrying in {} seconds: {e}",
+                            cli_args.data_dir_retry_interval
+                        );
+                    }
+                    if cli_args.data_dir_retry_interval > 3 {
+                        sleep(Duration::from_secs(cli_args.data_dir_retry_interval - 3)).await;
+                    }
+                }
+            }
+        }
+        if scheduler.schedule().is_oneshot() {
+            break;
+        }
+        sleep(Duration::from_secs(3)).await;
+    }
+}
+
+async fn read_rpc_watchlist_balances(
+    rpc_url: &str,
+    network_id: NetworkId,
+    watchlist: &[(String, ScriptPublicKey)],
+    start_time_ms: i64,
+    amount_in_sompi: bool,
+) -> Result<Vec<WatchlistBalance>, Box<dyn Error>> {
+    let node = WrpcNode::connect(rpc_url, network_id).await?;
+    let addresses: Vec<String> = watchlist.iter().map(|(address, _)| address.clone()).collect();
+    let result = address_balances(&node, &network_id.to_string(), &addresses).await;
+    if let Err(e) = node.disconnect().await {
+        warn!("Failed to disconnect from {rpc_url}: {e}");
+    }
+    let (_, balances) = result?;
+    Ok(watchlist
+        .iter()
+        .map(|(address, spk)| {
+            let (amount, utxo_count) = balances.get(address).copied().unwrap_or_default();
+            WatchlistBalance {
+                timestamp: start_time_ms,
+                address: address.clone(),
+                script_public_key: spk.script().to_vec(),
+                amount: if amount_in_sompi { amount } else { amount / SOMPI_PER_KASPA } as i64,
+                utxo_count: utxo_count as i64,
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,103 @@
+use crate::addresses::labels::Label;
+use crate::consensus::sync::SyncLimits;
+use crate::export::results::{export_results, ExportResults, PreviousSnapshot};
+use crate::export::settings::RunSettings;
+use crate::snapshot::anchor::SnapshotMode;
+use crate::snapshot::source::{scan_utxo_set, ScanSource};
+use kaspa_consensus_core::config::params::Params;
+use kaspa_consensus_core::constants::SOMPI_PER_KASPA;
+use kaspa_consensus_core::network::NetworkId;
+use kaspa_consensus_core::tx::ScriptPublicKey;
+use simply_kaspa_utxo_exporter_cli::cli_args::CliArgs;
+use simply_kaspa_utxo_exporter_scan::aggregate::{aggregate, AggregationOptions, ScriptClass};
+use simply_kaspa_utxo_exporter_scan::metrics::dormancy::{self, BlockRate, Threshold};
+use simply_kaspa_utxo_exporter_scan::scanner::ScanOptions;
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Inputs of a run besides the arguments
+pub struct RunContext<'a> {
+    pub network_id: NetworkId,
+    pub start_time_ms: i64,
+    pub previous: &'a PreviousSnapshot,
+    pub watchlist: &'a [(String, ScriptPublicKey)],
+    pub labels: &'a HashMap<ScriptPublicKey, Label>,
+    pub settings: &'a RunSettings,
+    pub snapshot_mode: SnapshotMode,
+}
+
+/// Scans the UTXO set and aggregates it into the rows of the run
+pub fn scan_and_export(
+    cli_args: &CliArgs,
+    run: Arc<AtomicBool>,
+    scan_source: &ScanSource,
+    context: &RunContext,
+) -> Result<ExportResults, Box<dyn Error>> {
+    let RunContext { network_id, previous, watchlist, labels, settings, snapshot_mode, .. } = *context;
+    let (scan, summary) = scan_utxo_set(
+        run.clone(),
+        network_id,
+        scan_source,
+        ScanOptions {
+            ignore_dust_amounts: cli_args.ignore_dust_amounts,
+            utxo_tier_schemes: cli_args.tier_schemes.clone(),
+            top_utxos_count: cli_args.top_utxos_count as usize,
+            watched_scripts: watchlist.iter().map(|(_, spk)| spk.clone()).collect(),
+            dormant_age: if settings.alert_rules.dormant_spent_amount.is_some() {
+                cli_args.alert_dormant_days * SECONDS_PER_DAY
+            } else {
+                0
+            },
+            dormancy_thresholds: dormancy_thresholds(cli_args, &previous.dormancy_totals),
+            genesis_daa_score: Params::from(network_id).genesis.daa_score,
+            block_rate: block_rate(&Params::from(network_id)),
+            utxo_count_threshold: cli_args.utxo_count_threshold,
+        },
+        &SyncLimits {
+            max_virtual_lag_ms: cli_args.max_sync_lag * 1000,
+            max_pruning_point_age_ms: cli_args.max_pruning_point_age * 3_600_000,
+        },
+        previous.pruning_point_daa_score,
+        snapshot_mode,
+        &previous.anchor_hash,
+    )?;
+    let options = AggregationOptions {
+        tier_schemes: cli_args.tier_schemes.clone(),
+        top_scripts_count: cli_args.top_scripts_count as usize,
+        top_scripts_min_amount: cli_args.top_scripts_min_amount.saturating_mul(SOMPI_PER_KASPA),
+        exclusion_count: settings.exclusions.rules.len(),
+        entities: !labels.is_empty(),
+    };
+    let aggregation = aggregate(
+        &scan.script_amounts,
+        &options,
+        |script| {
+            let label = if labels.is_empty() { None } else { labels.get(script) };
+            ScriptClass {
+                exclusion: settings.exclusions.matches(script, label),
+                entity: label.map(|l| (l.entity.as_str(), l.category.as_deref())),
+            }
+        },
+        &run,
+    )?;
+    Ok(export_results(cli_args, context, scan, summary, aggregation))
+}
+
+fn dormancy_thresholds(cli_args: &CliArgs, previous: &HashMap<String, (u64, u64)>) -> Vec<Threshold> {
+    let mut cutoffs: Vec<(String, dormancy::Cutoff)> =
+        cli_args.dormancy_days.iter().map(|days| (format!("{days}d"), dormancy::Cutoff::Age(days * SECONDS_PER_DAY))).collect();
+    if cli_args.dormancy_genesis_days > 0 {
+        cutoffs.push(("genesis".to_string(), dormancy::Cutoff::GenesisWindow(cli_args.dormancy_genesis_days * SECONDS_PER_DAY)));
+    }
+    cutoffs.into_iter().map(|(name, cutoff)| Threshold::new(name.clone(), cutoff, previous.get(&name).copied())).collect()
+}
+
+/// The network's blocks per second before and after the BPS activation
+fn block_rate(params: &Params) -> BlockRate {
+    let bps = params.bps();
+    BlockRate { bps_before: bps.before(), bps_after: bps.after(), activation_daa_score: bps.activation().daa_score() }
+}
@@ -0,0 +1,107 @@
+use crate::addresses::exclusions::Exclusions;
+use crate::alerts::rules::AlertRules;
+use crate::alerts::webhook::WebhookNotifier;
+use crate::comparison::guardrails::GuardrailLimits;
+use crate::schedule::scheduler::{Schedule, Scheduler};
+use chrono::Utc;
+use kaspa_addresses::Prefix;
+use kaspa_consensus_core::constants::SOMPI_PER_KASPA;
+use kaspa_consensus_core::network::NetworkId;
+use log::{error, info, warn};
+use simply_kaspa_utxo_exporter_cli::cli_args::CliArgs;
+use std::collections::HashSet;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Settings derived from the arguments, rebuilt when the configuration is reloaded
+pub struct RunSettings {
+    pub schedule: Schedule,
+    pub exclusions: Exclusions,
+    pub alert_rules: AlertRules,
+    pub notifier: Option<WebhookNotifier>,
+    pub guardrail_limits: GuardrailLimits,
+}
+
+impl RunSettings {
+    pub fn new(cli_args: &CliArgs, network_id: NetworkId, run: &Arc<AtomicBool>) -> Result<Self, String> {
+        let schedule = Schedule::new(cli_args.interval_minutes, cli_args.align_interval, cli_args.schedule.as_deref())
+            .map_err(|e| format!("Invalid schedule: {e}"))?;
+        let mut tier_scheme_names = HashSet::new();
+        for scheme in cli_args.tier_schemes.iter() {
+            if !tier_scheme_names.insert(&scheme.name) {
+                return Err(format!("Duplicate tier scheme name: {}", scheme.name));
+            }
+            info!("Using tier scheme {scheme}");
+        }
+        let exclusions = Exclusions::new(
+            &cli_args.exclude_addresses,
+            &cli_args.exclude_script_patterns,
+            &cli_args.exclude_categories,
+            Prefix::from(network_id),
+        )?;
+        for rule in exclusions.rules.iter() {
+            info!("Excluding {} {} from tiers and concentration metrics", rule.kind(), rule.value());
+        }
+        let alert_rules = AlertRules {
+            top_change_rank: cli_args.alert_top_change_rank,
+            top_change_amount: cli_args
+                .alert_top_change_amount
+                .map(|amount| if cli_args.amount_in_sompi { amount * SOMPI_PER_KASPA } else { amount } as i64),
+            entered_rank: cli_args.alert_entered_rank,
+            dormant_spent_amount: cli_args.alert_dormant_spent_amount.map(|amount| amount * SOMPI_PER_KASPA),
+        };
+        let notifier = if cli_args.webhook_url.is_empty() || alert_rules.is_empty() {
+            None
+        } else {
+            info!("Posting alerts to {} webhook(s)", cli_args.webhook_url.len());
+            match WebhookNotifier::new(
+                cli_args.webhook_url.clone(),
+                network_id.to_string(),
+                cli_args.webhook_retry_count,
+                Duration::from_secs(cli_args.webhook_retry_interval),
+                cli_args.webhook_dead_letter_file.clone(),
+                run.clone(),
+            ) {
+                Ok(notifier) => Some(notifier),
+                Err(e) => return Err(format!("Failed to create webhook client: {e}")),
+            }
+        };
+        let guardrail_limits = GuardrailLimits {
+            supply_drop_percent: cli_args.guardrail_supply_drop_percent,
+            script_count_drop_percent: cli_args.guardrail_script_count_drop_percent,
+            top_10_replaced: cli_args.guardrail_top_10_replaced,
+        };
+        Ok(RunSettings { schedule, exclusions, alert_rules, notifier, guardrail_limits })
+    }
+}
+
+/// Re-reads --config-file, keeps the current configuration if the new one is invalid
+pub fn reload(
+    run: &Arc<AtomicBool>,
+    cli_args: &mut CliArgs,
+    settings: &mut RunSettings,
+    scheduler: &mut Scheduler,
+    network_id: NetworkId,
+) {
+    info!("Reloading configuration");
+    let mut reloaded = match cli_args.read_config_file() {
+        Ok(reloaded) => reloaded,
+        Err(e) => {
+            error!("Failed to reload configuration, keeping the current one: {e}");
+            return;
+        }
+    };
+    for option in reloaded.keep_startup_options(cli_args) {
+        warn!("Ignoring changed {option}, it only takes effect after a restart");
+    }
+    match RunSettings::new(&reloaded, network_id, run) {
+        Ok(reloaded_settings) => {
+            scheduler.reschedule(reloaded_settings.schedule.clone(), reloaded.catch_up, Utc::now());
+            *settings = reloaded_settings;
+            *cli_args = reloaded;
+            info!("Configuration reloaded, run schedule: {}, next run at {}", scheduler.schedule(), scheduler.next_run());
+        }
+        Err(e) => error!("Failed to reload configuration, keeping the current one: {e}"),
+    }
+}
@@ -1 +1,11 @@
+pub mod addresses;
+pub mod alerts;
+pub mod comparison;
+pub mod consensus;
+pub mod control;
+pub mod export;
+pub mod metrics;
+pub mod rpc;
+pub mod schedule;
 pub mod signal;
+pub mod snapshot;
@@ -1,31 +1,38 @@
-use chrono::{DateTime, TimeDelta, TimeZone, Timelike, Utc};
+use chrono::{DateTime, TimeZone, Timelike, Utc};
 use clap::Parser;
 use humantime::format_duration;
-use kaspa_consensus::consensus::storage::ConsensusStorage;
-use kaspa_consensus_core::config::ConfigBuilder;
-use kaspa_consensus_core::constants::SOMPI_PER_KASPA;
-use kaspa_consensus_core::tx::ScriptPublicKey;
-use kaspa_database::prelude::{StoreError, DB};
-use kaspa_txscript::extract_script_pub_key_address;
+use kaspa_addresses::Prefix;
+use kaspa_consensus_core::config::params::Params;
 use kaspa_wrpc_client::prelude::NetworkId;
-use log::{debug, error, info, trace, warn};
-use regex::Regex;
-use rocksdb::{DBWithThreadMode, MultiThreaded};
+use log::{error, info, trace, warn};
+use simply_kaspa_utxo_exporter::addresses::labels::load_labels;
+use simply_kaspa_utxo_exporter::addresses::watchlist::load_watchlist;
+use simply_kaspa_utxo_exporter::alerts::rules::AlertCooldown;
+use simply_kaspa_utxo_exporter::comparison::guardrails;
+use simply_kaspa_utxo_exporter::consensus::multi_consensus::get_db_path;
+use simply_kaspa_utxo_exporter::consensus::sync::RunSkipped;
+use simply_kaspa_utxo_exporter::control::endpoint;
+use simply_kaspa_utxo_exporter::control::state::Control;
+use simply_kaspa_utxo_exporter::export::commit::{commit_to_db_with_retry, quarantine_with_retry, record_skipped_run};
+use simply_kaspa_utxo_exporter::export::results::PreviousSnapshot;
+use simply_kaspa_utxo_exporter::export::rpc_balances::export_rpc_balances;
+use simply_kaspa_utxo_exporter::export::scan::{scan_and_export, RunContext};
+use simply_kaspa_utxo_exporter::export::settings::{reload, RunSettings};
+use simply_kaspa_utxo_exporter::schedule::scheduler::Scheduler;
 use simply_kaspa_utxo_exporter::signal::signal_handler::notify_on_signals;
-use simply_kaspa_utxo_exporter_cli::cli_args::CliArgs;
+use simply_kaspa_utxo_exporter::snapshot::anchor::{PruningPointUnchanged, SnapshotMode};
+use simply_kaspa_utxo_exporter::snapshot::dump::dump_utxo_set;
+use simply_kaspa_utxo_exporter::snapshot::source::{read_virtual_daa_score, ScanSource};
+use simply_kaspa_utxo_exporter_cli::cli_args::{CliArgs, Command};
 use simply_kaspa_utxo_exporter_database::client::KaspaDbClient;
-use simply_kaspa_utxo_exporter_database::models::distribution_tier::DistributionTier;
-use simply_kaspa_utxo_exporter_database::models::script_utxo_count::ScriptUtxoCount;
-use simply_kaspa_utxo_exporter_database::models::top_script::TopScript;
-use std::cmp::Reverse;
-use std::collections::{BinaryHeap, HashMap};
-use std::error::Error;
-use std::path::PathBuf;
+use simply_kaspa_utxo_exporter_database::models::quarantined_snapshot::QuarantinedSnapshot;
+use simply_kaspa_utxo_exporter_scan::dump::format::DumpReader;
 use std::str::FromStr;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 use std::{env, fs};
+use tokio::net::TcpListener;
 use tokio::task;
 use tokio::time::sleep;
 
@@ -37,19 +44,60 @@ async fn main() {
     println!("------------------------------------------------------------");
     println!("- https://github.com/supertypo/simply-kaspa-utxo-exporter/ -");
     println!("------------------------------------------------------------");
-    let cli_args = CliArgs::parse();
+    let mut cli_args = CliArgs::parse().read_config_file().unwrap_or_else(|e| panic!("{e}"));
 
     env::set_var("RUST_LOG", &cli_args.log_level);
     env::set_var("RUST_LOG_STYLE", if cli_args.log_no_color { "never" } else { "always" });
     env_logger::builder().target(env_logger::Target::Stdout).format_target(false).format_timestamp_millis().init();
 
     let run = Arc::new(AtomicBool::new(true));
-    task::spawn(notify_on_signals(run.clone()));
+    let control = Arc::new(Control::default());
+    task::spawn(notify_on_signals(run.clone(), control.clone()));
 
     info!("{} {}", env!("CARGO_PKG_NAME"), cli_args.version());
     trace!("{:?}", cli_args);
 
+    if let (Some(listen), Some(token_file)) = (cli_args.control_listen.as_ref(), cli_args.control_token_file.as_ref()) {
+        let token = match fs::read_to_string(token_file) {
+            Ok(token) if !token.trim().is_empty() => token.trim().to_string(),
+            Ok(_) => panic!("Control token file {token_file} is empty"),
+            Err(e) => panic!("Failed to read control token file {token_file}: {e}"),
+        };
+        let listener = TcpListener::bind(listen).await.unwrap_or_else(|e| panic!("Failed to bind control endpoint to {listen}: {e}"));
+        info!("Control endpoint listening on {listen}");
+        task::spawn(endpoint::serve(listener, token, control.clone()));
+    }
+
     let network_id = NetworkId::from_str(&cli_args.network).unwrap();
+    if let Some(Command::Dump { output }) = cli_args.command.as_ref() {
+        let db_path = get_db_path(cli_args.base_dir.clone(), cli_args.consensus_dir.clone(), network_id)
+            .unwrap_or_else(|e| panic!("Unable to locate consensus directory in {}: {e}", cli_args.base_dir));
+        let snapshot_mode = SnapshotMode::new(None, cli_args.pruning_point_snapshots);
+        if let Err(e) = dump_utxo_set(run.clone(), network_id, &db_path, snapshot_mode, output) {
+            panic!("Failed to dump UTXO set to {output}: {e}");
+        }
+        return;
+    }
+    let dump_header = match cli_args.command.as_ref() {
+        Some(Command::Import { input }) => {
+            let header = match DumpReader::open(input) {
+                Ok(reader) => reader.header().clone(),
+                Err(e) => panic!("Failed to open dump file {input}: {e}"),
+            };
+            if header.network != network_id.to_string() {
+                panic!("Dump file {input} is for network {}, expected {network_id}", header.network);
+            }
+            info!(
+                "Importing {} snapshot at DAA score {} from {input}, created {}",
+                header.anchor_mode,
+                header.daa_score,
+                Utc.timestamp_millis_opt(header.created_ms as i64).unwrap()
+            );
+            Some(header)
+        }
+        _ => None,
+    };
+    let mut settings = RunSettings::new(&cli_args, network_id, &run).unwrap_or_else(|e| panic!("{e}"));
 
     let mut dbs = vec![];
     let mut last_run_ms = 0;
@@ -78,280 +126,229 @@ async fn main() {
             Err(e) => panic!("Database connection to {url} FAILED: {e}"),
         }
     }
-    info!("Run interval is set to {} minutes", cli_args.interval_minutes);
+    let last_run = (last_run_ms > 0).then(|| DateTime::from_timestamp_millis(last_run_ms).unwrap());
+    let mut scheduler = Scheduler::new(settings.schedule.clone(), cli_args.catch_up, last_run, Utc::now());
+    if let Some(rpc_url) = cli_args.rpc_url.clone() {
+        export_rpc_balances(run.clone(), control, cli_args, settings, network_id, &rpc_url, &dbs, scheduler).await;
+        return;
+    }
+    let mut previous = PreviousSnapshot { timestamp: last_run_ms, ..Default::default() };
+    if last_run_ms > 0 && dump_header.is_none() {
+        match dbs[0].select_top_scripts(last_run_ms).await {
+            Ok(top_scripts) => previous.top_scripts = top_scripts,
+            Err(e) => panic!("Failed to read previous top scripts from {}: {e}", dbs[0].url_cleaned),
+        }
+        match dbs[0].select_snapshot_totals(last_run_ms).await {
+            Ok(Some((total_supply, script_count))) => {
+                previous.total_supply = total_supply;
+                previous.script_count = script_count;
+            }
+            Ok(None) => {}
+            Err(e) => panic!("Failed to read previous snapshot totals from {}: {e}", dbs[0].url_cleaned),
+        }
+        match dbs[0].select_dormant_supply(last_run_ms).await {
+            Ok(rows) => {
+                previous.dormancy_totals = rows
+                    .into_iter()
+                    .map(|(threshold, max_block_daa_score, amount_sompi)| {
+                        (threshold, (max_block_daa_score as u64, amount_sompi as u64))
+                    })
+                    .collect();
+            }
+            Err(e) => panic!("Failed to read previous dormant supply from {}: {e}", dbs[0].url_cleaned),
+        }
+        match dbs[0].select_snapshot_anchor(last_run_ms).await {
+            Ok(anchor) => previous.anchor_hash = anchor.map(|(_, block_hash)| block_hash).unwrap_or_default(),
+            Err(e) => panic!("Failed to read previous snapshot anchor from {}: {e}", dbs[0].url_cleaned),
+        }
+    }
+    let mut guardrail_retries = 0;
+    let mut alert_cooldown = AlertCooldown::default();
+    let snapshot_mode = if dump_header.is_some() {
+        SnapshotMode::Virtual
+    } else {
+        SnapshotMode::new(cli_args.target_daa_score, cli_args.pruning_point_snapshots)
+    };
+    if dump_header.is_some() {
+        info!("Running once from the dump file");
+    } else if let Some(target_daa_score) = snapshot_mode.target_daa_score() {
+        info!("Taking a single snapshot once the virtual DAA score passes {target_daa_score}");
+    } else {
+        info!("Run schedule: {}, next run at {}", scheduler.schedule(), scheduler.next_run());
+    }
 
-    let run_interval = TimeDelta::minutes(cli_args.interval_minutes as i64);
+    // A requested run is kept until it completes or is cancelled, so a failed one is retried like a scheduled run
+    let mut run_requested = false;
     while run.load(Ordering::Relaxed) {
-        let start_time = Utc::now().with_nanosecond(0).unwrap();
-        let start_time_ms = start_time.timestamp_millis();
+        if control.take_reload_request() {
+            reload(&run, &mut cli_args, &mut settings, &mut scheduler, network_id);
+        }
+        let now = Utc::now().with_nanosecond(0).unwrap();
+        run_requested |= control.take_run_request();
+        let due = scheduler.due(now).or(run_requested.then_some(now));
+        let start_time = due.unwrap_or(now);
+        let start_time_ms = dump_header.as_ref().map_or(start_time.timestamp_millis(), |header| header.created_ms as i64);
         let last_run = DateTime::from_timestamp_millis(last_run_ms).unwrap();
         let last_run_delta = start_time.signed_duration_since(last_run);
-        if last_run_delta >= run_interval || run_interval.is_zero() {
+        if due.is_some() || snapshot_mode.target_daa_score().is_some() || dump_header.is_some() {
             info!("Reading tiers and top scripts");
             if last_run_ms > 0 {
                 info!("Time since last run: {}", format_duration(last_run_delta.to_std().unwrap()));
             }
-            let db_path = match get_db_path(cli_args.base_dir.clone(), cli_args.consensus_dir.clone(), network_id) {
-                Ok(db_path) => db_path,
-                Err(e) => {
-                    warn!("Unable to locate consensus directory in {}: {}", cli_args.base_dir, e);
-                    continue;
+            let scan_source = match cli_args.command.as_ref() {
+                Some(Command::Import { input }) => ScanSource::DumpFile(input.clone()),
+                _ => match get_db_path(cli_args.base_dir.clone(), cli_args.consensus_dir.clone(), network_id) {
+                    Ok(db_path) => ScanSource::Consensus(db_path),
+                    Err(e) => {
+                        warn!(
+                            "Unable to locate consensus directory in {}, retrying in {} seconds: {}",
+                            cli_args.base_dir, cli_args.data_dir_retry_interval, e
+                        );
+                        sleep(Duration::from_secs(cli_args.data_dir_retry_interval)).await;
+                        continue;
+                    }
+                },
+            };
+            if let (Some(target_daa_score), ScanSource::Consensus(db_path)) = (snapshot_mode.target_daa_score(), &scan_source) {
+                match read_virtual_daa_score(network_id, db_path) {
+                    Ok(virtual_daa_score) if virtual_daa_score < target_daa_score => {
+                        let bps = Params::from(network_id).bps().get(virtual_daa_score);
+                        let wait_secs = ((target_daa_score - virtual_daa_score) / bps / 2).clamp(3, 60);
+                        info!("Virtual DAA score is {virtual_daa_score}, waiting for {target_daa_score} (next check in {wait_secs}s)");
+                        sleep(Duration::from_secs(wait_secs)).await;
+                        continue;
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        warn!("Failed to read virtual DAA score, retrying in {} seconds: {e}", cli_args.data_dir_retry_interval);
+                        sleep(Duration::from_secs(cli_args.data_dir_retry_interval)).await;
+                        continue;
+                    }
                 }
+            }
+            let watchlist = load_watchlist(cli_args.watchlist_file.clone(), &dbs, Prefix::from(network_id)).await;
+            let labels = load_labels(cli_args.labels_file.clone(), Prefix::from(network_id));
+            let scan_run = control.start_scan();
+            let context = RunContext {
+                network_id,
+                start_time_ms,
+                previous: &previous,
+                watchlist: &watchlist,
+                labels: &labels,
+                settings: &settings,
+                snapshot_mode,
             };
-            match read_tiers_and_top_scripts(cli_args.clone(), run.clone(), network_id, db_path.clone(), start_time_ms) {
-                Ok((tiers, top_scripts, script_utxo_counts)) => {
-                    commit_to_db_with_retry(
-                        run.clone(),
-                        cli_args.db_retry_count,
-                        cli_args.db_retry_interval,
-                        dbs.clone(),
-                        &tiers,
-                        &top_scripts,
-                        &script_utxo_counts,
-                    )
-                    .await;
-                    last_run_ms = start_time_ms;
-                    info!("Finished reading tiers and top scripts, waiting until next interval ({}m)", cli_args.interval_minutes);
+            let scan_results = scan_and_export(&cli_args, scan_run.clone(), &scan_source, &context);
+            control.finish_scan();
+            match scan_results {
+                Ok(mut results) => {
+                    let report = guardrails::check(&settings.guardrail_limits, &previous.stats(), &results.stats());
+                    if !report.violations.is_empty() && guardrail_retries < cli_args.guardrail_retry_count {
+                        guardrail_retries += 1;
+                        warn!(
+                            "Snapshot failed sanity checks, rescanning in {} seconds ({guardrail_retries}/{}): {}",
+                            cli_args.data_dir_retry_interval,
+                            cli_args.guardrail_retry_count,
+                            report.violations.join("; ")
+                        );
+                        if cli_args.data_dir_retry_interval > 3 {
+                            sleep(Duration::from_secs(cli_args.data_dir_retry_interval - 3)).await;
+                        }
+                    } else if !report.violations.is_empty() {
+                        guardrail_retries = 0;
+                        let reasons = report.violations.join("; ");
+                        error!("Snapshot failed sanity checks, quarantining instead of committing: {reasons}");
+                        let quarantined = QuarantinedSnapshot {
+                            timestamp: start_time_ms,
+                            virtual_daa_score: results.supply_reconciliation.virtual_daa_score,
+                            total_supply: results.supply_reconciliation.scanned_supply,
+                            script_count: results.concentration_metrics.script_count,
+                            top_10_replaced: report.top_10_replaced as i32,
+                            reasons,
+                        };
+                        quarantine_with_retry(
+                            run.clone(),
+                            cli_args.db_retry_count,
+                            cli_args.db_retry_interval,
+                            dbs.clone(),
+                            &quarantined,
+                        )
+                        .await;
+                        last_run_ms = start_time_ms;
+                        info!("Quarantined snapshot, waiting until the next run");
+                    } else {
+                        guardrail_retries = 0;
+                        commit_to_db_with_retry(
+                            run.clone(),
+                            cli_args.db_retry_count,
+                            cli_args.db_retry_interval,
+                            dbs.clone(),
+                            &results,
+                        )
+                        .await;
+                        last_run_ms = start_time_ms;
+                        if let Some(notifier) = settings.notifier.as_ref() {
+                            let cooldown_ms = cli_args.alert_cooldown_minutes as i64 * 60_000;
+                            notifier.notify(alert_cooldown.filter(results.alerts, cooldown_ms));
+                        }
+                        previous = PreviousSnapshot {
+                            timestamp: start_time_ms,
+                            script_amounts: results.script_amounts.take(),
+                            top_scripts: results.top_scripts,
+                            dormant_amounts: results.dormant_amounts,
+                            total_supply: results.supply_reconciliation.scanned_supply,
+                            script_count: results.concentration_metrics.script_count,
+                            dormancy_totals: results.dormancy_totals,
+                            pruning_point_daa_score: results.pruning_point_daa_score,
+                            anchor_hash: results.snapshot_anchor.block_hash,
+                        };
+                        info!("Finished reading tiers and top scripts, waiting until the next run");
+                    }
                 }
                 Err(e) => {
                     if !run.load(Ordering::Relaxed) {
                         break;
                     }
-                    error!("Failed to read tiers and top scripts, retrying in {} seconds: {e}", cli_args.data_dir_retry_interval);
-                    if cli_args.data_dir_retry_interval > 3 {
-                        sleep(Duration::from_secs(cli_args.data_dir_retry_interval - 3)).await;
+                    if !scan_run.load(Ordering::Relaxed) {
+                        warn!("Scan cancelled, waiting until the next run");
+                        run_requested = false;
+                        scheduler.completed(start_time, Utc::now());
+                        info!("Next run at {}", scheduler.next_run());
+                    } else if let Some(unchanged) = e.downcast_ref::<PruningPointUnchanged>() {
+                        info!("Skipping run, {unchanged}, waiting until the next run");
+                        last_run_ms = start_time_ms;
+                    } else if let Some(skipped) = e.downcast_ref::<RunSkipped>() {
+                        warn!(
+                            "Node is not synced, skipping run and retrying in {} seconds: {skipped}",
+                            cli_args.data_dir_retry_interval
+                        );
+                        record_skipped_run(&dbs, start_time_ms, skipped).await;
+                        if cli_args.data_dir_retry_interval > 3 {
+                            sleep(Duration::from_secs(cli_args.data_dir_retry_interval - 3)).await;
+                        }
+                    } else {
+                        error!("Failed to read tiers and top scripts, retrying in {} seconds: {e}", cli_args.data_dir_retry_interval);
+                        if cli_args.data_dir_retry_interval > 3 {
+                            sleep(Duration::from_secs(cli_args.data_dir_retry_interval - 3)).await;
+                        }
                     }
                 }
             };
         }
-        if run_interval.is_zero() {
+        if scheduler.schedule().is_oneshot()
+            || dump_header.is_some()
+            || (snapshot_mode.target_daa_score().is_some() && last_run_ms == start_time_ms)
+        {
             break;
         }
-        sleep(Duration::from_secs(3)).await;
-    }
-}
-
-fn get_db_path(base_dir: String, consensus_dir: Option<String>, network_id: NetworkId) -> std::io::Result<PathBuf> {
-    let consensus_base_path = PathBuf::from(base_dir).join(network_id.to_prefixed()).join("datadir").join("consensus");
-
-    if let Some(consensus_dir) = consensus_dir {
-        let consensus_path = consensus_base_path.join(consensus_dir);
-        info!("Using specified consensus directory: {}", consensus_path.display());
-        Ok(consensus_path)
-    } else {
-        let re = Regex::new(r"^consensus-(\d+)$").unwrap();
-        let consensus_dir = fs::read_dir(&consensus_base_path)?
-            .filter_map(|e| e.ok())
-            .filter_map(|e| e.file_name().into_string().ok())
-            .filter(|name| re.is_match(name))
-            .max_by(|a, b| b.cmp(a))
-            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "No matching consensus directory found"))?;
-        let consensus_path = consensus_base_path.join(consensus_dir);
-        info!("Using auto-detected consensus directory: {}", consensus_path.display());
-        Ok(consensus_path)
-    }
-}
-
-async fn commit_to_db_with_retry(
-    run: Arc<AtomicBool>,
-    db_retry_count: u16,
-    db_retry_interval: u64,
-    dbs: Vec<KaspaDbClient>,
-    tiers: &[DistributionTier],
-    top_scripts: &[TopScript],
-    script_utxo_counts: &[ScriptUtxoCount],
-) {
-    for db in dbs {
-        debug!(
-            "Committing {} tiers, {} top scripts and {} script utxo counts to {}",
-            tiers.len(),
-            top_scripts.len(),
-            script_utxo_counts.len(),
-            db.url_cleaned
-        );
-        for retry in 0..=db_retry_count {
-            match commit_to_db(&db, tiers, top_scripts, script_utxo_counts).await {
-                Ok(()) => {
-                    info!(
-                        "Committed {} tiers, {} top scripts and {} script utxo counts to {}",
-                        tiers.len(),
-                        top_scripts.len(),
-                        script_utxo_counts.len(),
-                        db.url_cleaned
-                    );
-                    break;
-                }
-                Err(e) => {
-                    let start_sleep_time = Instant::now();
-                    error!("Failed to commit results to {}, retry {retry}/{db_retry_count}: {e}", db.url_cleaned);
-                    while start_sleep_time.elapsed() < Duration::from_secs(db_retry_interval) {
-                        if !run.load(Ordering::Relaxed) {
-                            return;
-                        }
-                        sleep(Duration::from_secs(3)).await;
-                    }
-                }
-            }
-        }
-    }
-}
-
-async fn commit_to_db(
-    db: &KaspaDbClient,
-    tiers: &[DistributionTier],
-    top_scripts: &[TopScript],
-    script_utxo_counts: &[ScriptUtxoCount],
-) -> Result<(), Box<dyn Error>> {
-    db.insert_distribution_tiers(tiers).await?;
-    db.insert_top_scripts(top_scripts).await?;
-    db.replace_script_utxo_counts(script_utxo_counts).await?;
-    Ok(())
-}
-
-fn read_tiers_and_top_scripts(
-    cli_args: CliArgs,
-    run: Arc<AtomicBool>,
-    network_id: NetworkId,
-    db_path: PathBuf,
-    start_time_ms: i64,
-) -> Result<(Vec<DistributionTier>, Vec<TopScript>, Vec<ScriptUtxoCount>), Box<dyn Error>> {
-    let mut tiers = [(0u64, 0u64); 11]; // Covers up to 10b KAS
-    let top_scripts_count = if cli_args.top_scripts_count == 0 { u64::MAX } else { cli_args.top_scripts_count };
-
-    let initial_heap = if cli_args.top_scripts_count == 0 { 1_000_000 } else { cli_args.top_scripts_count };
-    let mut top_scripts_heap: BinaryHeap<Reverse<(u64, Vec<u8>)>> = BinaryHeap::with_capacity(initial_heap as usize);
-
-    let prefix = kaspa_addresses::Prefix::from(network_id);
-    let mut script_utxo_counts = vec![];
-
-    for (script, (amount, utxo_count)) in read_script_amounts(run.clone(), network_id, cli_args.ignore_dust_amounts, db_path)? {
-        let amount_kas = amount / SOMPI_PER_KASPA;
-        let tier = ((amount_kas * 10) as f64).log10().floor() as usize;
-        tiers[tier].0 += 1;
-        tiers[tier].1 += amount;
-
-        if amount_kas >= cli_args.top_scripts_min_amount {
-            if top_scripts_heap.len() < top_scripts_count as usize {
-                top_scripts_heap.push(Reverse((amount, script.script().to_vec())));
-            } else if amount > top_scripts_heap.peek().unwrap().0 .0 {
-                top_scripts_heap.pop();
-                top_scripts_heap.push(Reverse((amount, script.script().to_vec())));
-            }
+        if due.is_some() && last_run_ms == start_time_ms {
+            run_requested = false;
+            scheduler.completed(start_time, Utc::now());
+            info!("Next run at {}", scheduler.next_run());
         }
-        if utxo_count >= cli_args.utxo_count_threshold {
-            script_utxo_counts.push(ScriptUtxoCount {
-                script_public_key: script.script().to_vec(),
-                script_public_key_address: cli_args
-                    .extract_addresses
-                    .then(|| extract_script_pub_key_address(&script, prefix).ok().map(|a| a.payload_to_string()))
-                    .flatten(),
-                count: utxo_count as i64,
-            });
-        }
-        if !run.load(Ordering::Relaxed) {
-            return Err(StoreError::DataInconsistency("Shutting down".to_string()).into());
-        }
-    }
-
-    let mut distribution_tiers = vec![];
-    for (idx, (count, amount)) in tiers.into_iter().enumerate() {
-        let amount_kas = amount / SOMPI_PER_KASPA;
-        info!("Tier {idx}, count: {count}, total: {amount_kas} KAS");
-        distribution_tiers.push(DistributionTier {
-            tier: idx as i16,
-            timestamp: start_time_ms,
-            count: count as i64,
-            amount: amount_kas as i64,
-        });
+        sleep(Duration::from_secs(3)).await;
     }
-
-    let top_scripts = top_scripts_heap
-        .into_sorted_vec()
-        .into_iter()
-        .enumerate()
-        .map(|(idx, Reverse((amount, spk)))| {
-            let amount_kas = amount / SOMPI_PER_KASPA;
-            if idx < 10 {
-                let address = extract_script_pub_key_address(&ScriptPublicKey::from_vec(0, spk.clone()), prefix).unwrap();
-                info!("Top {} address: {address}, total: {amount_kas} KAS", idx + 1);
-            }
-            TopScript {
-                rank: idx as i32,
-                timestamp: start_time_ms,
-                script_public_key: spk.clone(),
-                script_public_key_address: cli_args
-                    .extract_addresses
-                    .then(|| ScriptPublicKey::from_vec(0, spk))
-                    .map(|spk| extract_script_pub_key_address(&spk, prefix).unwrap())
-                    .map(|a| a.payload_to_string()),
-                amount: if cli_args.amount_in_sompi { amount } else { amount_kas } as i64,
-            }
-        })
-        .collect();
-
-    Ok((distribution_tiers, top_scripts, script_utxo_counts))
-}
-
-fn read_script_amounts(
-    run: Arc<AtomicBool>,
-    network_id: NetworkId,
-    ignore_dust_amounts: u64,
-    db_path: PathBuf,
-) -> Result<HashMap<ScriptPublicKey, (u64, u64)>, Box<dyn Error>> {
-    let mut opts = rocksdb::Options::default();
-    opts.create_if_missing(false);
-    opts.set_max_open_files(128);
-    opts.set_allow_mmap_reads(true);
-    let guard = kaspa_utils::fd_budget::acquire_guard(128).unwrap();
-
-    info!("Reading UTXOs from VirtualStore");
-    let db = Arc::new(DB::new(<DBWithThreadMode<MultiThreaded>>::open_for_read_only(&opts, db_path.to_str().unwrap(), false)?, guard));
-    let config = Arc::new(ConfigBuilder::new(network_id.into()).adjust_perf_params_to_consensus_params().build());
-    let storage = ConsensusStorage::new(db, config);
-
-    let mut count = 0u64;
-    let mut total_amount = 0u64;
-    let mut dust_count = 0u64;
-    let mut dust_total_amount = 0u64;
-    let mut script_amount = HashMap::new();
-
-    let start_time = Instant::now();
-    for result in storage.virtual_stores.read().utxo_set.iterator() {
-        let (_, entry) = result?;
-        let amount = entry.amount;
-        count += 1;
-        total_amount += amount;
-        if amount < ignore_dust_amounts {
-            trace!("Ignoring dust UTXO of {amount} sompi");
-            dust_count += 1;
-            dust_total_amount += amount;
-        } else {
-            script_amount
-                .entry(entry.script_public_key.clone())
-                .and_modify(|(a, c)| {
-                    *a += amount;
-                    *c += 1;
-                })
-                .or_insert((amount, 1));
-        }
-        if count % 1_000_000 == 0 {
-            info!(
-                "Processed {count} UTXOs, total amount {} KAS (dust: {dust_count}/{}, scripts: {})",
-                total_amount / SOMPI_PER_KASPA,
-                dust_total_amount / SOMPI_PER_KASPA,
-                script_amount.len()
-            );
-        }
-        if !run.load(Ordering::Relaxed) {
-            return Err(StoreError::DataInconsistency("Shutting down".to_string()).into());
-        }
+    if let Some(notifier) = settings.notifier {
+        notifier.close().await;
     }
-    info!(
-        "Done processing {count} UTXOs, total amount {} (dust: {dust_count}/{}, scripts: {}), time used: {}",
-        total_amount / SOMPI_PER_KASPA,
-        dust_total_amount / SOMPI_PER_KASPA,
-        script_amount.len(),
-        format_duration(Duration::from_secs(start_time.elapsed().as_secs()))
-    );
-    Ok(script_amount)
 }
@@ -0,0 +1,56 @@
+pub struct Concentration {
+    pub gini: f64,
+    pub hhi: f64,
+    pub scripts_50_percent: u64,
+    pub scripts_90_percent: u64,
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub p999: u64,
+}
+
+/// Calculates concentration metrics from balances sorted in ascending order
+pub fn calculate(sorted_balances: &[u64]) -> Concentration {
+    let n = sorted_balances.len() as u128;
+    let total: u128 = sorted_balances.iter().map(|&b| b as u128).sum();
+    if n == 0 || total == 0 {
+        return Concentration { gini: 0.0, hhi: 0.0, scripts_50_percent: 0, scripts_90_percent: 0, p50: 0, p90: 0, p99: 0, p999: 0 };
+    }
+    let weighted_sum: u128 = sorted_balances.iter().enumerate().map(|(i, &b)| (i as u128 + 1) * b as u128).sum();
+    let gini = (2.0 * weighted_sum as f64) / (n as f64 * total as f64) - (n as f64 + 1.0) / n as f64;
+    let hhi = sorted_balances
+        .iter()
+        .map(|&b| {
+            let share = b as f64 / total as f64;
+            share * share
+        })
+        .sum();
+    Concentration {
+        gini,
+        hhi,
+        scripts_50_percent: scripts_controlling(sorted_balances, total, 50),
+        scripts_90_percent: scripts_controlling(sorted_balances, total, 90),
+        p50: percentile(sorted_balances, 500),
+        p90: percentile(sorted_balances, 900),
+        p99: percentile(sorted_balances, 990),
+        p999: percentile(sorted_balances, 999),
+    }
+}
+
+/// Returns the minimum number of (largest) scripts holding at least `percent` of the total
+fn scripts_controlling(sorted_balances: &[u64], total: u128, percent: u128) -> u64 {
+    let mut acc = 0u128;
+    for (idx, &b) in sorted_balances.iter().rev().enumerate() {
+        acc += b as u128;
+        if acc * 100 >= total * percent {
+            return idx as u64 + 1;
+        }
+    }
+    sorted_balances.len() as u64
+}
+
+/// Nearest-rank percentile, `permille` is the percentile multiplied by 10
+fn percentile(sorted_balances: &[u64], permille: usize) -> u64 {
+    let rank = (sorted_balances.len() * permille).div_ceil(1000).max(1);
+    sorted_balances[rank - 1]
+}
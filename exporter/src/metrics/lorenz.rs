@@ -0,0 +1,22 @@
+/// Samples the Lorenz curve of balances sorted in ascending order.
+/// Returns (population share, supply share) pairs, both in percent, for each of the given population shares.
+pub fn sample(sorted_balances: &[u64], population_shares: &[f64]) -> Vec<(f64, f64)> {
+    let total: u128 = sorted_balances.iter().map(|&b| b as u128).sum();
+    let mut points: Vec<f64> = population_shares.iter().map(|p| p.clamp(0.0, 100.0)).collect();
+    points.sort_by(f64::total_cmp);
+    points.dedup();
+
+    let mut curve = Vec::with_capacity(points.len());
+    let mut idx = 0usize;
+    let mut acc = 0u128;
+    for point in points {
+        let end = ((sorted_balances.len() as f64 * point / 100.0).round() as usize).min(sorted_balances.len());
+        while idx < end {
+            acc += sorted_balances[idx] as u128;
+            idx += 1;
+        }
+        let supply_share = if total == 0 { 0.0 } else { acc as f64 * 100.0 / total as f64 };
+        curve.push((point, supply_share));
+    }
+    curve
+}
@@ -0,0 +1,3 @@
+pub mod concentration;
+pub mod lorenz;
+pub mod supply;
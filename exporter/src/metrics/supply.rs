@@ -0,0 +1,59 @@
+use kaspa_consensus::processes::coinbase::CoinbaseManager;
+use kaspa_consensus_core::config::params::Params;
+
+/// Expected supply (in sompi) at `daa_score` following the network's emission schedule.
+/// The pre-deflationary phase, the deflationary phase before and after the BPS activation are summed separately,
+/// as the block subsidy is scaled down by the BPS change at activation.
+pub fn network_supply(params: &Params, daa_score: u64) -> u128 {
+    let coinbase_manager = CoinbaseManager::new(
+        params.coinbase_payload_script_public_key_max_len,
+        params.max_coinbase_payload_len,
+        params.deflationary_phase_daa_score,
+        params.pre_deflationary_phase_base_subsidy,
+        params.bps(),
+    );
+    let boundaries = [params.deflationary_phase_daa_score, params.bps().activation().daa_score()];
+    expected_supply(|daa_score| coinbase_manager.calc_block_subsidy(daa_score), &boundaries, daa_score)
+}
+
+/// Sums the block subsidy for every DAA score up to (excluding) `daa_score`.
+/// `boundaries` are the DAA scores where the emission schedule changes phase, each phase is summed on its own.
+/// Within a phase the subsidy must be a non-increasing step function, so each constant run is located by galloping
+/// instead of evaluating every DAA score.
+pub fn expected_supply(subsidy: impl Fn(u64) -> u64, boundaries: &[u64], daa_score: u64) -> u128 {
+    let mut boundaries: Vec<u64> = boundaries.iter().copied().filter(|&b| b > 0 && b < daa_score).collect();
+    boundaries.sort_unstable();
+    boundaries.dedup();
+    boundaries.push(daa_score);
+    let mut start = 0u64;
+    let mut total = 0u128;
+    for end in boundaries {
+        total += phase_supply(&subsidy, start, end);
+        start = end;
+    }
+    total
+}
+
+/// Sums the block subsidy for the DAA scores in `start..end`
+fn phase_supply(subsidy: &impl Fn(u64) -> u64, mut start: u64, end: u64) -> u128 {
+    let mut total = 0u128;
+    while start < end {
+        let value = subsidy(start);
+        // Find the last DAA score (below end) with the same subsidy
+        let mut step = 1u64;
+        let mut last = start;
+        while last + step < end && subsidy(last + step) == value {
+            last += step;
+            step *= 2;
+        }
+        while step > 1 {
+            step /= 2;
+            if last + step < end && subsidy(last + step) == value {
+                last += step;
+            }
+        }
+        total += value as u128 * (last - start + 1) as u128;
+        start = last + 1;
+    }
+    total
+}
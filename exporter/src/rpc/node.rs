@@ -0,0 +1,59 @@
+use crate::consensus::sync::RunSkipped;
+use kaspa_addresses::Address;
+use log::info;
+use std::collections::HashMap;
+use std::error::Error;
+use std::future::Future;
+
+/// Max addresses per GetUtxosByAddresses request
+pub const ADDRESS_BATCH_SIZE: usize = 1_000;
+
+pub struct NodeInfo {
+    pub network_id: String,
+    pub is_synced: bool,
+    pub has_utxo_index: bool,
+    pub virtual_daa_score: u64,
+}
+
+/// The node RPC calls used by the exporter, implemented by the wRPC client and by mocks in tests.
+pub trait NodeRpc {
+    fn node_info(&self) -> impl Future<Output = Result<NodeInfo, Box<dyn Error>>>;
+
+    /// Returns an (address, amount) pair per UTXO of the given addresses, requires the node to run with --utxoindex
+    fn utxos_by_addresses(&self, addresses: &[Address]) -> impl Future<Output = Result<Vec<(String, u64)>, Box<dyn Error>>>;
+}
+
+/// Reads (amount, utxo count) per address, every requested address is present in the result
+pub async fn address_balances(
+    rpc: &impl NodeRpc,
+    network_id: &str,
+    addresses: &[String],
+) -> Result<(u64, HashMap<String, (u64, u64)>), Box<dyn Error>> {
+    let node_info = rpc.node_info().await?;
+    if node_info.network_id != network_id {
+        return Err(format!("Node is on network {}, expected {network_id}", node_info.network_id).into());
+    }
+    if !node_info.has_utxo_index {
+        return Err("Node is not running with --utxoindex".into());
+    }
+    if !node_info.is_synced {
+        return Err(RunSkipped {
+            reason: "node reports it is not synced".to_string(),
+            virtual_daa_score: node_info.virtual_daa_score,
+            virtual_lag_ms: 0,
+            pruning_point_age_ms: 0,
+        }
+        .into());
+    }
+    let addresses = addresses.iter().map(|a| Address::try_from(a.as_str())).collect::<Result<Vec<_>, _>>()?;
+    let mut balances: HashMap<String, (u64, u64)> = addresses.iter().map(|a| (a.to_string(), (0, 0))).collect();
+    for batch in addresses.chunks(ADDRESS_BATCH_SIZE) {
+        for (address, utxo_amount) in rpc.utxos_by_addresses(batch).await? {
+            let (amount, utxo_count) = balances.entry(address).or_default();
+            *amount += utxo_amount;
+            *utxo_count += 1;
+        }
+    }
+    info!("Read balances of {} addresses at virtual DAA score {}", balances.len(), node_info.virtual_daa_score);
+    Ok((node_info.virtual_daa_score, balances))
+}
@@ -0,0 +1,47 @@
+use crate::rpc::node::{NodeInfo, NodeRpc};
+use kaspa_addresses::Address;
+use kaspa_wrpc_client::prelude::{ConnectOptions, ConnectStrategy, NetworkId, RpcApi};
+use kaspa_wrpc_client::{KaspaRpcClient, WrpcEncoding};
+use std::error::Error;
+use std::time::Duration;
+
+/// Kaspad wRPC (Borsh) connection
+pub struct WrpcNode {
+    client: KaspaRpcClient,
+}
+
+impl WrpcNode {
+    pub async fn connect(url: &str, network_id: NetworkId) -> Result<Self, Box<dyn Error>> {
+        let client = KaspaRpcClient::new(WrpcEncoding::Borsh, Some(url), None, Some(network_id), None)?;
+        let options = ConnectOptions {
+            block_async_connect: true,
+            strategy: ConnectStrategy::Fallback,
+            connect_timeout: Some(Duration::from_secs(10)),
+            ..Default::default()
+        };
+        client.connect(Some(options)).await?;
+        Ok(WrpcNode { client })
+    }
+
+    pub async fn disconnect(&self) -> Result<(), Box<dyn Error>> {
+        self.client.disconnect().await?;
+        Ok(())
+    }
+}
+
+impl NodeRpc for WrpcNode {
+    async fn node_info(&self) -> Result<NodeInfo, Box<dyn Error>> {
+        let info = self.client.get_server_info().await?;
+        Ok(NodeInfo {
+            network_id: info.network_id.to_string(),
+            is_synced: info.is_synced,
+            has_utxo_index: info.has_utxo_index,
+            virtual_daa_score: info.virtual_daa_score,
+        })
+    }
+
+    async fn utxos_by_addresses(&self, addresses: &[Address]) -> Result<Vec<(String, u64)>, Box<dyn Error>> {
+        let entries = self.client.get_utxos_by_addresses(addresses.to_vec()).await?;
+        Ok(entries.into_iter().filter_map(|e| Some((e.address?.to_string(), e.utxo_entry.amount))).collect())
+    }
+}
@@ -0,0 +1,127 @@
+use chrono::{DateTime, Datelike, NaiveDate, TimeDelta, Timelike, Utc};
+use std::fmt;
+use std::str::FromStr;
+
+/// A five field cron expression (minute hour day-of-month month weekday), evaluated in UTC.
+/// Fields accept `*`, values, ranges, steps and lists, e.g. `*/15`, `1-5`, `0,30`, weekdays are 0-7 (0 and 7 = Sunday).
+/// As in cron, a day matches either field when both day-of-month and weekday are restricted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl CronSchedule {
+    /// The first matching minute after `time`, None if there is none within eight years
+    pub fn next_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut t = DateTime::from_timestamp((time.timestamp().div_euclid(60) + 1) * 60, 0)?;
+        let limit = t + TimeDelta::days(8 * 366);
+        while t < limit {
+            if !bit(self.months, t.month()) {
+                let (year, month) = if t.month() == 12 { (t.year() + 1, 1) } else { (t.year(), t.month() + 1) };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?.and_utc();
+            } else if !self.day_matches(t.date_naive()) {
+                t = t.date_naive().succ_opt()?.and_hms_opt(0, 0, 0)?.and_utc();
+            } else if !bit(self.hours, t.hour()) {
+                t = t.with_minute(0)? + TimeDelta::hours(1);
+            } else if !bit(self.minutes, t.minute()) {
+                t += TimeDelta::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day = bit(self.days, date.day());
+        let weekday = bit(self.weekdays, date.weekday().num_days_from_sunday());
+        if self.days_restricted && self.weekdays_restricted {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expression = match s.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            expression => expression,
+        };
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minutes, hours, days, months, weekdays] = fields[..] else {
+            return Err(format!("Expected five cron fields (minute hour day month weekday), got '{s}'"));
+        };
+        let mut weekdays_mask = parse_field(weekdays, 0, 7).map_err(|e| format!("Invalid weekday field in '{s}': {e}"))?;
+        if bit(weekdays_mask, 7) {
+            weekdays_mask = (weekdays_mask | 1) & !(1 << 7);
+        }
+        let schedule = CronSchedule {
+            expression: s.trim().to_string(),
+            minutes: parse_field(minutes, 0, 59).map_err(|e| format!("Invalid minute field in '{s}': {e}"))?,
+            hours: parse_field(hours, 0, 23).map_err(|e| format!("Invalid hour field in '{s}': {e}"))?,
+            days: parse_field(days, 1, 31).map_err(|e| format!("Invalid day field in '{s}': {e}"))?,
+            months: parse_field(months, 1, 12).map_err(|e| format!("Invalid month field in '{s}': {e}"))?,
+            weekdays: weekdays_mask,
+            days_restricted: !days.starts_with('*'),
+            weekdays_restricted: !weekdays.starts_with('*'),
+        };
+        if schedule.next_after(DateTime::UNIX_EPOCH).is_none() {
+            return Err(format!("Cron expression '{s}' never matches"));
+        }
+        Ok(schedule)
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expression)
+    }
+}
+
+fn bit(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+/// Parses a comma separated list of `*`, `<n>`, `<n>-<m>`, optionally followed by `/<step>`, into a bit mask
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, Some(step.parse::<u32>().map_err(|e| format!("invalid step '{step}': {e}"))?)),
+            None => (part, None),
+        };
+        let parse = |v: &str| v.parse::<u32>().map_err(|e| format!("invalid value '{v}': {e}"));
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse(start)?, parse(end)?)
+        } else {
+            let value = parse(range)?;
+            (value, if step.is_some() { max } else { value })
+        };
+        if start < min || end > max || start > end {
+            return Err(format!("'{part}' is outside {min}-{max}"));
+        }
+        match step {
+            Some(0) => return Err(format!("step must be > 0 in '{part}'")),
+            Some(step) => (start..=end).step_by(step as usize).for_each(|v| mask |= 1 << v),
+            None => (start..=end).for_each(|v| mask |= 1 << v),
+        }
+    }
+    Ok(mask)
+}
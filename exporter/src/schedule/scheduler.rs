@@ -0,0 +1,133 @@
+use crate::schedule::cron::CronSchedule;
+use chrono::{DateTime, TimeDelta, Utc};
+use simply_kaspa_utxo_exporter_cli::cli_args::CatchUp;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Schedule {
+    /// Runs the interval after the previous run started
+    Interval(TimeDelta),
+    /// Runs at multiples of the interval since the Unix epoch, e.g. every full hour
+    Aligned(TimeDelta),
+    Cron(CronSchedule),
+}
+
+impl Schedule {
+    pub fn new(interval_minutes: u64, align_interval: bool, cron: Option<&str>) -> Result<Self, String> {
+        let interval = TimeDelta::minutes(interval_minutes as i64);
+        if let Some(cron) = cron {
+            Ok(Schedule::Cron(cron.parse()?))
+        } else if align_interval {
+            if interval.is_zero() {
+                return Err("Aligned runs require an interval > 0".to_string());
+            }
+            Ok(Schedule::Aligned(interval))
+        } else {
+            Ok(Schedule::Interval(interval))
+        }
+    }
+
+    /// A zero interval runs once
+    pub fn is_oneshot(&self) -> bool {
+        matches!(self, Schedule::Interval(interval) if interval.is_zero())
+    }
+
+    /// The next run after one started at `time`
+    pub fn next_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Schedule::Interval(interval) => Some(time + *interval),
+            Schedule::Aligned(interval) => {
+                let secs = interval.num_seconds();
+                DateTime::from_timestamp((time.timestamp().div_euclid(secs) + 1) * secs, 0)
+            }
+            Schedule::Cron(cron) => cron.next_after(time),
+        }
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Schedule::Interval(interval) if interval.is_zero() => write!(f, "once"),
+            Schedule::Interval(interval) => write!(f, "every {} minutes", interval.num_minutes()),
+            Schedule::Aligned(interval) => write!(f, "every {} minutes, aligned to UTC", interval.num_minutes()),
+            Schedule::Cron(cron) => write!(f, "cron '{cron}' (UTC)"),
+        }
+    }
+}
+
+/// Tracks when the next run is due.
+/// Aligned and cron runs are timestamped with their scheduled time, so runs of several exporters line up.
+pub struct Scheduler {
+    schedule: Schedule,
+    catch_up: CatchUp,
+    next_run: DateTime<Utc>,
+    catching_up: bool,
+    last_run: Option<DateTime<Utc>>,
+}
+
+impl Scheduler {
+    /// Without a previous run, the first run counts as missed
+    pub fn new(schedule: Schedule, catch_up: CatchUp, last_run: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Self {
+        let mut scheduler = Scheduler { schedule, catch_up, next_run: now, catching_up: false, last_run: None };
+        match last_run {
+            Some(last_run) => scheduler.completed(last_run, now),
+            None => scheduler.missed(now),
+        }
+        scheduler
+    }
+
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    pub fn next_run(&self) -> DateTime<Utc> {
+        self.next_run
+    }
+
+    /// Returns the timestamp for the run if one is due
+    pub fn due(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if now < self.next_run {
+            None
+        } else if self.catching_up || matches!(self.schedule, Schedule::Interval(_)) {
+            Some(now)
+        } else {
+            Some(self.next_run)
+        }
+    }
+
+    /// Replaces the schedule, the next run is recalculated from the last one
+    pub fn reschedule(&mut self, schedule: Schedule, catch_up: CatchUp, now: DateTime<Utc>) {
+        self.schedule = schedule;
+        self.catch_up = catch_up;
+        match self.last_run {
+            Some(last_run) => self.completed(last_run, now),
+            None => self.missed(now),
+        }
+    }
+
+    /// Schedules the run after the one started at `start`, checking for runs missed in the meantime
+    pub fn completed(&mut self, start: DateTime<Utc>, now: DateTime<Utc>) {
+        self.last_run = Some(start);
+        match self.schedule.next_after(start) {
+            Some(next_run) if next_run > now => {
+                self.next_run = next_run;
+                self.catching_up = false;
+            }
+            _ => self.missed(now),
+        }
+    }
+
+    fn missed(&mut self, now: DateTime<Utc>) {
+        match self.catch_up {
+            CatchUp::Once => {
+                self.next_run = now;
+                self.catching_up = true;
+            }
+            CatchUp::Skip => {
+                self.next_run = self.schedule.next_after(now).unwrap_or(now);
+                self.catching_up = false;
+            }
+        }
+    }
+}
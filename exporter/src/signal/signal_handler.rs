@@ -2,24 +2,35 @@ use std::process;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-use log::warn;
+use crate::control::state::Control;
+use log::{info, warn};
 #[cfg(windows)]
 use tokio::signal::ctrl_c;
 #[cfg(unix)]
 use tokio::signal::unix::{signal, SignalKind};
 
-pub async fn notify_on_signals(run: Arc<AtomicBool>) {
+pub async fn notify_on_signals(run: Arc<AtomicBool>, control: Arc<Control>) {
     #[cfg(unix)]
     {
         let mut sigterm = signal(SignalKind::terminate()).expect("Failed to set up SIGTERM handler");
         let mut sigint = signal(SignalKind::interrupt()).expect("Failed to set up SIGINT handler");
+        let mut sigusr1 = signal(SignalKind::user_defined1()).expect("Failed to set up SIGUSR1 handler");
+        let mut sighup = signal(SignalKind::hangup()).expect("Failed to set up SIGHUP handler");
         loop {
             tokio::select! {
                 _ = sigint.recv() => {
-                    exit(run.clone(), "SIGINT");
+                    exit(run.clone(), &control, "SIGINT");
                 },
                 _ = sigterm.recv() => {
-                    exit(run.clone(), "SIGTERM");
+                    exit(run.clone(), &control, "SIGTERM");
+                },
+                _ = sigusr1.recv() => {
+                    info!("SIGUSR1 received, requesting a run");
+                    control.request_run();
+                },
+                _ = sighup.recv() => {
+                    info!("SIGHUP received, requesting a configuration reload");
+                    control.request_reload();
                 },
             }
         }
@@ -30,18 +41,19 @@ pub async fn notify_on_signals(run: Arc<AtomicBool>) {
         loop {
             tokio::select! {
                 _ = ctrl_c => {
-                    exit(run, "Ctrl+C");
+                    exit(run, &control, "Ctrl+C");
                 },
             }
         }
     }
 }
 
-fn exit(run: Arc<AtomicBool>, signal: &str) {
+fn exit(run: Arc<AtomicBool>, control: &Control, signal: &str) {
     if !run.load(Ordering::Relaxed) {
         warn!("{} received, terminating...", signal);
         process::exit(1);
     }
     warn!("{} received, stopping... (repeat for forced close)", signal);
     run.store(false, Ordering::Relaxed);
+    control.cancel_scan();
 }
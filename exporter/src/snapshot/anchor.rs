@@ -0,0 +1,52 @@
+use std::error::Error;
+use std::fmt;
+
+/// Which chain position a snapshot is anchored to.
+#[derive(Clone, Copy)]
+pub enum SnapshotMode {
+    /// The virtual UTXO set whenever the run interval fires
+    Virtual,
+    /// The virtual UTXO set once the virtual DAA score passes the target
+    TargetDaaScore(u64),
+    /// Kaspad's stored pruning point UTXO set, once per pruning point
+    PruningPoint,
+}
+
+impl SnapshotMode {
+    pub fn new(target_daa_score: Option<u64>, pruning_point: bool) -> Self {
+        match (target_daa_score, pruning_point) {
+            (_, true) => SnapshotMode::PruningPoint,
+            (Some(target_daa_score), false) => SnapshotMode::TargetDaaScore(target_daa_score),
+            (None, false) => SnapshotMode::Virtual,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SnapshotMode::Virtual => "virtual",
+            SnapshotMode::TargetDaaScore(_) => "target_daa_score",
+            SnapshotMode::PruningPoint => "pruning_point",
+        }
+    }
+
+    pub fn target_daa_score(&self) -> Option<u64> {
+        match self {
+            SnapshotMode::TargetDaaScore(target_daa_score) => Some(*target_daa_score),
+            _ => None,
+        }
+    }
+}
+
+/// Returned in place of a scan when the pruning point hasn't moved since the previous snapshot.
+#[derive(Debug)]
+pub struct PruningPointUnchanged {
+    pub daa_score: u64,
+}
+
+impl fmt::Display for PruningPointUnchanged {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pruning point at DAA score {} is already snapshotted", self.daa_score)
+    }
+}
+
+impl Error for PruningPointUnchanged {}
@@ -0,0 +1,59 @@
+use crate::snapshot::anchor::SnapshotMode;
+use crate::snapshot::source::utxo_set_kind;
+use chrono::{Timelike, Utc};
+use humantime::format_duration;
+use kaspa_consensus_core::constants::SOMPI_PER_KASPA;
+use kaspa_consensus_core::network::NetworkId;
+use log::info;
+use simply_kaspa_utxo_exporter_scan::dump::format::{DumpHeader, DumpWriter};
+use simply_kaspa_utxo_exporter_scan::scanner::ScanCancelled;
+use simply_kaspa_utxo_exporter_scan::source::consensus::{open_storage, ConsensusSource};
+use simply_kaspa_utxo_exporter_scan::source::dump_file::record_from_utxo;
+use simply_kaspa_utxo_exporter_scan::source::UtxoSource;
+use std::error::Error;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Writes the UTXO set of the snapshot mode to a dump file for a later import
+pub fn dump_utxo_set(
+    run: Arc<AtomicBool>,
+    network_id: NetworkId,
+    db_path: &Path,
+    snapshot_mode: SnapshotMode,
+    output: &str,
+) -> Result<(), Box<dyn Error>> {
+    let storage = open_storage(network_id, db_path)?;
+    let source = ConsensusSource::new(&storage, utxo_set_kind(snapshot_mode))?;
+    let anchor = source.anchor();
+    info!("Dumping the {} UTXO set at DAA score {}", anchor.mode, anchor.daa_score);
+    let header = DumpHeader {
+        network: network_id.to_string(),
+        anchor_mode: snapshot_mode.name().to_string(),
+        anchor_hash: anchor.block_hash,
+        daa_score: anchor.daa_score,
+        created_ms: Utc::now().with_nanosecond(0).unwrap().timestamp_millis() as u64,
+    };
+    let mut writer = DumpWriter::create(output, &header)?;
+    let start_time = Instant::now();
+    for result in source.utxos()? {
+        let (outpoint, entry) = result?;
+        writer.write(&record_from_utxo(&outpoint, &entry))?;
+        if writer.utxo_count().is_multiple_of(1_000_000) {
+            info!("Dumped {} UTXOs", writer.utxo_count());
+        }
+        if !run.load(Ordering::Relaxed) {
+            return Err(ScanCancelled.into());
+        }
+    }
+    let total_amount = writer.total_amount();
+    let count = writer.finish()?;
+    info!(
+        "Dumped {count} UTXOs, total amount {} KAS at DAA score {} to {output}, time used: {}",
+        total_amount / SOMPI_PER_KASPA,
+        header.daa_score,
+        format_duration(Duration::from_secs(start_time.elapsed().as_secs()))
+    );
+    Ok(())
+}
@@ -0,0 +1,3 @@
+pub mod anchor;
+pub mod dump;
+pub mod source;
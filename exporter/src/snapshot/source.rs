@@ -0,0 +1,106 @@
+use crate::consensus::sync::{self, SyncIndicators, SyncLimits};
+use crate::snapshot::anchor::{PruningPointUnchanged, SnapshotMode};
+use chrono::Utc;
+use kaspa_consensus::model::stores::headers::HeaderStoreReader;
+use kaspa_consensus::model::stores::pruning::PruningStoreReader;
+use kaspa_consensus::model::stores::virtual_state::VirtualStateStoreReader;
+use kaspa_consensus_core::network::NetworkId;
+use log::info;
+use simply_kaspa_utxo_exporter_scan::scanner::{self, ScanOptions, ScanResult};
+use simply_kaspa_utxo_exporter_scan::source::consensus::{open_storage, ConsensusSource, UtxoSetKind};
+use simply_kaspa_utxo_exporter_scan::source::dump_file::DumpFileSource;
+use simply_kaspa_utxo_exporter_scan::source::UtxoSource;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+/// Where a run reads its UTXO set from
+pub enum ScanSource {
+    /// The consensus directory of a local kaspad
+    Consensus(PathBuf),
+    /// A file written by the dump subcommand
+    DumpFile(String),
+}
+
+pub struct ScanSummary {
+    /// DAA score the scanned UTXO set corresponds to
+    pub daa_score: u64,
+    pub anchor_mode: String,
+    pub anchor_hash: Vec<u8>,
+    pub pruning_point_daa_score: u64,
+}
+
+/// Checks the node is synced and scans the UTXO set of the snapshot mode.
+/// A pruning point snapshot already taken returns `PruningPointUnchanged`.
+pub fn scan_utxo_set(
+    run: Arc<AtomicBool>,
+    network_id: NetworkId,
+    scan_source: &ScanSource,
+    options: ScanOptions,
+    sync_limits: &SyncLimits,
+    previous_pruning_point_daa_score: u64,
+    snapshot_mode: SnapshotMode,
+    previous_anchor_hash: &[u8],
+) -> Result<(ScanResult, ScanSummary), Box<dyn Error>> {
+    match scan_source {
+        ScanSource::Consensus(db_path) => {
+            let storage = open_storage(network_id, db_path)?;
+            let virtual_state = storage.virtual_stores.read().state.get()?;
+            info!("Virtual DAA score: {}", virtual_state.daa_score);
+            let pruning_point = storage.pruning_point_store.read().pruning_point()?;
+            let pruning_point_daa_score = storage.headers_store.get_daa_score(pruning_point)?;
+            sync::check(
+                sync_limits,
+                &SyncIndicators {
+                    now_ms: Utc::now().timestamp_millis() as u64,
+                    virtual_daa_score: virtual_state.daa_score,
+                    virtual_past_median_time_ms: virtual_state.past_median_time,
+                    pruning_point_timestamp_ms: storage.headers_store.get_timestamp(pruning_point)?,
+                    pruning_point_daa_score,
+                    previous_pruning_point_daa_score,
+                },
+            )?;
+            let source = ConsensusSource::new(&storage, utxo_set_kind(snapshot_mode))?;
+            let anchor = source.anchor().clone();
+            if matches!(snapshot_mode, SnapshotMode::PruningPoint) && previous_anchor_hash == anchor.block_hash.as_slice() {
+                return Err(PruningPointUnchanged { daa_score: anchor.daa_score }.into());
+            }
+            info!("Reading UTXOs from the {} UTXO set at DAA score {}", anchor.mode, anchor.daa_score);
+            let result = scanner::scan(&source, options, &run)?;
+            let summary = ScanSummary {
+                daa_score: anchor.daa_score,
+                anchor_mode: snapshot_mode.name().to_string(),
+                anchor_hash: anchor.block_hash.to_vec(),
+                pruning_point_daa_score,
+            };
+            Ok((result, summary))
+        }
+        ScanSource::DumpFile(path) => {
+            let source = DumpFileSource::open(path)?;
+            let anchor = source.anchor().clone();
+            info!("Reading UTXOs from dump file {path}");
+            let result = scanner::scan(&source, options, &run)?;
+            let summary = ScanSummary {
+                daa_score: anchor.daa_score,
+                anchor_mode: anchor.mode,
+                anchor_hash: anchor.block_hash.to_vec(),
+                pruning_point_daa_score: previous_pruning_point_daa_score,
+            };
+            Ok((result, summary))
+        }
+    }
+}
+
+pub fn read_virtual_daa_score(network_id: NetworkId, db_path: &Path) -> Result<u64, Box<dyn Error>> {
+    let storage = open_storage(network_id, db_path)?;
+    let daa_score = storage.virtual_stores.read().state.get()?.daa_score;
+    Ok(daa_score)
+}
+
+pub fn utxo_set_kind(snapshot_mode: SnapshotMode) -> UtxoSetKind {
+    match snapshot_mode {
+        SnapshotMode::PruningPoint => UtxoSetKind::PruningPoint,
+        SnapshotMode::Virtual | SnapshotMode::TargetDaaScore(_) => UtxoSetKind::Virtual,
+    }
+}
@@ -0,0 +1,77 @@
+use kaspa_addresses::{Address, Prefix, Version};
+use kaspa_txscript::{extract_script_pub_key_address, pay_to_address_script};
+use simply_kaspa_utxo_exporter::addresses::exclusions::Exclusions;
+use simply_kaspa_utxo_exporter::addresses::labels::read_labels_file;
+use simply_kaspa_utxo_exporter::addresses::watchlist::parse_addresses;
+use simply_kaspa_utxo_exporter_scan::fixture::{non_standard_script, p2pk_ecdsa_script, p2pk_script, p2sh_script};
+
+fn address(prefix: Prefix, seed: u8) -> Address {
+    Address::new(prefix, Version::PubKey, &[seed; 32])
+}
+
+#[test]
+fn extract_standard_scripts() {
+    let a = extract_script_pub_key_address(&p2pk_script(1), Prefix::Mainnet).unwrap();
+    assert_eq!((a.version, a.payload.to_vec()), (Version::PubKey, vec![1; 32]));
+    assert_eq!(a, address(Prefix::Mainnet, 1));
+
+    let a = extract_script_pub_key_address(&p2pk_ecdsa_script(2), Prefix::Mainnet).unwrap();
+    let mut payload = vec![0x02];
+    payload.extend_from_slice(&[2; 32]);
+    assert_eq!((a.version, a.payload.to_vec()), (Version::PubKeyECDSA, payload));
+
+    let a = extract_script_pub_key_address(&p2sh_script(3), Prefix::Testnet).unwrap();
+    assert_eq!((a.prefix, a.version, a.payload.to_vec()), (Prefix::Testnet, Version::ScriptHash, vec![3; 32]));
+}
+
+#[test]
+fn extract_non_standard_script() {
+    assert!(extract_script_pub_key_address(&non_standard_script(4), Prefix::Mainnet).is_err());
+}
+
+#[test]
+fn watchlist_addresses() {
+    let first = address(Prefix::Mainnet, 1).to_string();
+    let second = address(Prefix::Mainnet, 2).to_string();
+    let watchlist = parse_addresses(
+        vec![first.clone(), address(Prefix::Testnet, 3).to_string(), "kaspa:invalid".to_string(), second.clone(), first.clone()],
+        Prefix::Mainnet,
+    );
+    assert_eq!(watchlist, vec![(first, p2pk_script(1)), (second, p2pk_script(2))]);
+}
+
+#[test]
+fn exclusions() {
+    let excluded = address(Prefix::Mainnet, 1).to_string();
+    let exclusions = Exclusions::new(&[excluded], &["^51".to_string()], &[], Prefix::Mainnet).unwrap();
+    assert_eq!(exclusions.matches(&p2pk_script(1), None), Some(0));
+    assert_eq!(exclusions.matches(&non_standard_script(4), None), Some(1));
+    assert_eq!(exclusions.matches(&p2pk_script(2), None), None);
+    assert_eq!(pay_to_address_script(&address(Prefix::Mainnet, 1)), p2pk_script(1));
+
+    assert!(Exclusions::new(&[address(Prefix::Testnet, 1).to_string()], &[], &[], Prefix::Mainnet).is_err());
+    assert!(Exclusions::new(&[], &["(".to_string()], &[], Prefix::Mainnet).is_err());
+    assert!(Exclusions::default().matches(&p2pk_script(1), None).is_none());
+}
+
+#[test]
+fn labels_file() {
+    let path = std::env::temp_dir().join(format!("labels-{}.csv", std::process::id()));
+    let lines = [
+        "# address,entity,category".to_string(),
+        format!("{},exchange", address(Prefix::Mainnet, 1)),
+        format!("{},exchange,cex", address(Prefix::Mainnet, 2)),
+        format!("{},exchange,custodian", address(Prefix::Mainnet, 3)),
+        format!("{},miner,pool", address(Prefix::Testnet, 4)),
+        format!("{},", address(Prefix::Mainnet, 5)),
+    ];
+    std::fs::write(&path, lines.join("\n")).unwrap();
+    let labels = read_labels_file(path.to_str().unwrap(), Prefix::Mainnet).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(labels.len(), 3);
+    assert_eq!(labels[&p2pk_script(1)].category, None);
+    // A conflicting category is replaced by the first one given for the entity
+    assert_eq!(labels[&p2pk_script(2)].category.as_deref(), Some("cex"));
+    assert_eq!(labels[&p2pk_script(3)].category.as_deref(), Some("cex"));
+    assert!(labels.values().all(|label| label.entity == "exchange"));
+}
@@ -0,0 +1,104 @@
+use kaspa_addresses::Prefix;
+use kaspa_consensus_core::tx::ScriptPublicKey;
+use simply_kaspa_utxo_exporter::alerts::rules::{dormant_alerts, top_script_alerts, Alert, AlertCooldown, AlertRules};
+use simply_kaspa_utxo_exporter_database::models::top_script_change::TopScriptChange;
+use std::collections::HashMap;
+
+const SOMPI_PER_KASPA: u64 = 100_000_000;
+
+fn rules() -> AlertRules {
+    AlertRules { top_change_rank: 100, top_change_amount: Some(1_000), entered_rank: Some(10), dormant_spent_amount: None }
+}
+
+fn change(script: u8, rank: Option<i32>, previous_rank: Option<i32>, amount_change: Option<i64>) -> TopScriptChange {
+    TopScriptChange {
+        timestamp: 2_000,
+        previous_timestamp: 1_000,
+        script_public_key: vec![script],
+        script_public_key_address: None,
+        change: String::new(),
+        rank,
+        previous_rank,
+        rank_change: None,
+        amount: 50_000,
+        previous_amount: None,
+        amount_change,
+    }
+}
+
+fn kinds(alerts: &[Alert]) -> Vec<(&'static str, String)> {
+    alerts.iter().map(|a| (a.kind(), a.script_public_key().to_string())).collect()
+}
+
+#[test]
+fn top_change_thresholds() {
+    let changes = [
+        change(1, Some(0), Some(0), Some(1_000)),
+        change(2, Some(0), Some(0), Some(-1_000)),
+        change(3, Some(0), Some(0), Some(999)),
+        change(4, Some(99), Some(99), Some(5_000)),
+        change(5, Some(100), Some(100), Some(5_000)),
+        // Dropped out of the top list, the previous rank counts
+        change(6, None, Some(5), Some(-5_000)),
+    ];
+    let alerts = top_script_alerts(&rules(), &changes);
+    let expected = ["01", "02", "04", "06"].map(|s| ("top_script_change", s.to_string()));
+    assert_eq!(kinds(&alerts), expected);
+    assert!(top_script_alerts(&AlertRules { top_change_amount: None, ..rules() }, &changes).is_empty());
+}
+
+#[test]
+fn entered_rank_threshold() {
+    let changes = [change(1, Some(9), None, None), change(2, Some(10), None, None), change(3, Some(2), Some(4), None)];
+    let alerts = top_script_alerts(&rules(), &changes);
+    assert_eq!(kinds(&alerts), [("top_script_entered", "01".to_string())]);
+    assert!(matches!(alerts[0], Alert::TopScriptEntered { rank: 9, amount: 50_000, .. }));
+}
+
+#[test]
+fn dormant_spent_threshold() {
+    let script = |n: u8| ScriptPublicKey::from_vec(0, vec![n]);
+    let previous =
+        HashMap::from([(script(1), 10 * SOMPI_PER_KASPA), (script(2), 10 * SOMPI_PER_KASPA), (script(3), 10 * SOMPI_PER_KASPA)]);
+    let current = HashMap::from([(script(1), 5 * SOMPI_PER_KASPA), (script(2), 6 * SOMPI_PER_KASPA)]);
+    let dormant_rules = AlertRules { dormant_spent_amount: Some(5 * SOMPI_PER_KASPA), ..rules() };
+    let mut alerts = dormant_alerts(&dormant_rules, 2_000, &previous, &current, Prefix::Mainnet, |a| (a / SOMPI_PER_KASPA) as i64);
+    alerts.sort_by(|a, b| a.script_public_key().cmp(b.script_public_key()));
+    assert_eq!(kinds(&alerts), [("dormant_spent", "01".to_string()), ("dormant_spent", "03".to_string())]);
+    assert!(matches!(alerts[1], Alert::DormantSpent { dormant_amount: 10, spent_amount: 10, .. }));
+    assert!(dormant_alerts(&rules(), 2_000, &previous, &current, Prefix::Mainnet, |a| a as i64).is_empty());
+}
+
+#[test]
+fn cooldown_suppresses_repeats() {
+    let entered = |script: u8, timestamp: i64| Alert::TopScriptEntered {
+        timestamp,
+        script_public_key: format!("{script:02x}"),
+        address: None,
+        rank: 1,
+        amount: 1,
+    };
+    let changed = |script: u8, timestamp: i64| Alert::TopScriptChange {
+        timestamp,
+        script_public_key: format!("{script:02x}"),
+        address: None,
+        rank: Some(1),
+        previous_rank: Some(1),
+        amount: 1,
+        amount_change: 1,
+    };
+    let hour = 3_600_000;
+    let mut cooldown = AlertCooldown::default();
+    // Duplicates within a run are dropped, other rules and scripts are kept
+    let alerts = cooldown.filter(vec![entered(1, 0), entered(1, 0), changed(1, 0), entered(2, 0)], hour);
+    assert_eq!(alerts.len(), 3);
+    // Within the cooldown
+    assert!(cooldown.filter(vec![entered(1, hour - 1), changed(1, hour - 1)], hour).is_empty());
+    // After the cooldown
+    assert_eq!(kinds(&cooldown.filter(vec![entered(1, hour), entered(3, hour)], hour)).len(), 2);
+
+    // Without a cooldown only duplicates within a run are dropped
+    let mut cooldown = AlertCooldown::default();
+    assert_eq!(cooldown.filter(vec![entered(1, 0), entered(1, 0)], 0).len(), 1);
+    assert_eq!(cooldown.filter(vec![entered(1, 1)], 0).len(), 1);
+}
@@ -0,0 +1,154 @@
+use simply_kaspa_utxo_exporter::comparison::guardrails::{check, GuardrailLimits, SnapshotStats};
+use simply_kaspa_utxo_exporter::comparison::tiers::{migrations, Migration, NO_TIER};
+use simply_kaspa_utxo_exporter::comparison::top_scripts::{compare, ENTERED, EXITED, RETAINED};
+use simply_kaspa_utxo_exporter_database::models::top_script::TopScript;
+use simply_kaspa_utxo_exporter_scan::tier_scheme::TierScheme;
+use std::collections::{BTreeMap, HashMap};
+
+fn limits() -> GuardrailLimits {
+    GuardrailLimits { supply_drop_percent: 10.0, script_count_drop_percent: 20.0, top_10_replaced: 3 }
+}
+
+fn top_script(timestamp: i64, script: u8, rank: i32, amount: i64) -> TopScript {
+    TopScript {
+        timestamp,
+        rank,
+        script_public_key: vec![script],
+        script_public_key_address: None,
+        amount,
+        entity: None,
+        category: None,
+    }
+}
+
+fn top_scripts(scripts: impl IntoIterator<Item = u8>) -> Vec<TopScript> {
+    scripts.into_iter().enumerate().map(|(i, s)| top_script(1_000, s, i as i32 + 1, 1_000 - i as i64)).collect()
+}
+
+fn stats(total_supply: i64, script_count: i64, top_scripts: &[TopScript]) -> SnapshotStats<'_> {
+    SnapshotStats { total_supply, script_count, top_scripts }
+}
+
+#[test]
+fn guardrails_without_previous_data() {
+    let current = top_scripts(1..=10);
+    let report = check(&limits(), &stats(0, 0, &[]), &stats(1_000, 100, &current));
+    assert_eq!(report.top_10_replaced, 0);
+    assert!(report.violations.is_empty());
+}
+
+#[test]
+fn guardrails_supply_drop() {
+    let report = check(&limits(), &stats(1_000, 100, &[]), &stats(950, 100, &[]));
+    assert!(report.violations.is_empty());
+    let report = check(&limits(), &stats(1_000, 100, &[]), &stats(750, 100, &[]));
+    assert_eq!(report.violations, vec!["total supply dropped 25.00% (1000 -> 750), limit is 10%"]);
+    // Growth is never a violation
+    let report = check(&limits(), &stats(1_000, 100, &[]), &stats(5_000, 100, &[]));
+    assert!(report.violations.is_empty());
+}
+
+#[test]
+fn guardrails_script_count_drop() {
+    let report = check(&limits(), &stats(1_000, 100, &[]), &stats(1_000, 85, &[]));
+    assert!(report.violations.is_empty());
+    let report = check(&limits(), &stats(1_000, 100, &[]), &stats(500, 50, &[]));
+    assert_eq!(
+        report.violations,
+        vec!["total supply dropped 50.00% (1000 -> 500), limit is 10%", "script count dropped 50.00% (100 -> 50), limit is 20%"]
+    );
+}
+
+#[test]
+fn guardrails_disabled_by_zero_limits() {
+    let disabled = GuardrailLimits { supply_drop_percent: 0.0, script_count_drop_percent: 0.0, top_10_replaced: 0 };
+    let previous = top_scripts(1..=10);
+    let current = top_scripts(11..=20);
+    let report = check(&disabled, &stats(1_000, 100, &previous), &stats(0, 0, &current));
+    // Replacements are still counted for the report
+    assert_eq!(report.top_10_replaced, 10);
+    assert!(report.violations.is_empty());
+}
+
+#[test]
+fn guardrails_top_10_replacement() {
+    let previous = top_scripts(1..=12);
+    // Scripts 11 and 12 were ranked below the previous top 10, scripts 9 and 10 fall below the current top 10
+    let current = top_scripts([1, 2, 3, 4, 5, 6, 7, 11, 12, 13, 9, 10]);
+    let report = check(&limits(), &stats(1_000, 100, &previous), &stats(1_000, 100, &current));
+    assert_eq!(report.top_10_replaced, 3);
+    assert!(report.violations.is_empty());
+
+    let current = top_scripts([1, 2, 3, 4, 5, 6, 11, 12, 13, 14]);
+    let report = check(&limits(), &stats(1_000, 100, &previous), &stats(1_000, 100, &current));
+    assert_eq!(report.top_10_replaced, 4);
+    assert_eq!(report.violations, vec!["4 of the top 10 scripts were replaced, limit is 3"]);
+}
+
+#[test]
+fn top_scripts_without_previous_snapshot() {
+    assert!(compare(2_000, &[], &top_scripts(1..=3), &HashMap::new()).is_empty());
+}
+
+#[test]
+fn top_scripts_changes() {
+    let previous = vec![top_script(1_000, 1, 1, 100), top_script(1_000, 2, 2, 50), top_script(1_000, 3, 3, 40)];
+    let current = vec![top_script(2_000, 2, 1, 200), top_script(2_000, 4, 2, 80), top_script(2_000, 5, 3, 60)];
+    // Script 3 was emptied and is missing from the current amounts
+    let current_amounts = HashMap::from([(vec![1], 30)]);
+    let changes = compare(2_000, &previous, &current, &current_amounts);
+
+    let summary: Vec<_> = changes
+        .iter()
+        .map(|c| {
+            assert_eq!((c.timestamp, c.previous_timestamp), (2_000, 1_000));
+            (
+                c.script_public_key[0],
+                c.change.as_str(),
+                c.rank,
+                c.previous_rank,
+                c.rank_change,
+                c.amount,
+                c.previous_amount,
+                c.amount_change,
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (2, RETAINED, Some(1), Some(2), Some(1), 200, Some(50), Some(150)),
+            (4, ENTERED, Some(2), None, None, 80, None, None),
+            (5, ENTERED, Some(3), None, None, 60, None, None),
+            (1, EXITED, None, Some(1), None, 30, Some(100), Some(-70)),
+            (3, EXITED, None, Some(3), None, 0, Some(40), Some(-40)),
+        ]
+    );
+}
+
+fn matrix(migrations: &BTreeMap<(i16, i16), Migration>) -> Vec<((i16, i16), u64, u64, u64)> {
+    migrations.iter().map(|(&k, m)| (k, m.count, m.amount, m.previous_amount)).collect()
+}
+
+#[test]
+fn tier_migrations() {
+    let schemes = vec![
+        TierScheme { name: "fine".to_string(), boundaries: vec![10, 100] },
+        TierScheme { name: "coarse".to_string(), boundaries: vec![1_000] },
+    ];
+    let previous = HashMap::from([("a", (5, 1)), ("b", (50, 1)), ("c", (500, 1))]);
+    let current = HashMap::from([("a", (50, 2)), ("b", (60, 1)), ("d", (7, 1))]);
+    let matrices = migrations(&schemes, &previous, &current);
+    assert_eq!(matrices.len(), 2);
+    assert_eq!(
+        matrix(&matrices[0]),
+        vec![((NO_TIER, 0), 1, 7, 0), ((0, 1), 1, 50, 5), ((1, 1), 1, 60, 50), ((2, NO_TIER), 1, 0, 500)]
+    );
+    assert_eq!(matrix(&matrices[1]), vec![((NO_TIER, 0), 1, 7, 0), ((0, NO_TIER), 1, 0, 500), ((0, 0), 2, 110, 55)]);
+}
+
+#[test]
+fn tier_migrations_without_schemes() {
+    let balances = HashMap::from([("a", (5, 1))]);
+    assert!(migrations(&[], &balances, &balances).is_empty());
+}
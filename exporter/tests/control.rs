@@ -0,0 +1,55 @@
+use simply_kaspa_utxo_exporter::control::endpoint::handle;
+use simply_kaspa_utxo_exporter::control::state::Control;
+use std::sync::atomic::Ordering;
+
+const TOKEN: &str = "s3cret";
+
+fn request(method: &str, path: &str, token: Option<&str>) -> String {
+    let authorization = token.map(|t| format!("Authorization: Bearer {t}\r\n")).unwrap_or_default();
+    format!("{method} {path} HTTP/1.1\r\nHost: localhost\r\n{authorization}Content-Length: 0\r\n\r\n")
+}
+
+#[test]
+fn requires_the_token() {
+    let control = Control::default();
+    assert_eq!(handle(&control, TOKEN, &request("POST", "/run", None)).0, 401);
+    assert_eq!(handle(&control, TOKEN, &request("POST", "/run", Some("wrong"))).0, 401);
+    assert_eq!(handle(&control, TOKEN, &request("POST", "/run", Some("s3cre"))).0, 401);
+    assert_eq!(handle(&control, TOKEN, &request("POST", "/unknown", None)).0, 401);
+    assert_eq!(handle(&control, TOKEN, "").0, 401);
+    assert!(!control.take_run_request());
+    let lowercase = "POST /run HTTP/1.1\r\nauthorization:  Bearer s3cret \r\n\r\n";
+    assert_eq!(handle(&control, TOKEN, lowercase).0, 202);
+}
+
+#[test]
+fn run_and_reload_requests() {
+    let control = Control::default();
+    assert_eq!(handle(&control, TOKEN, &request("POST", "/run", Some(TOKEN))).0, 202);
+    assert!(control.take_run_request());
+    assert!(!control.take_run_request());
+    assert_eq!(handle(&control, TOKEN, &request("POST", "/reload", Some(TOKEN))).0, 202);
+    assert!(control.take_reload_request());
+    assert!(!control.take_reload_request());
+    assert_eq!(handle(&control, TOKEN, &request("GET", "/run", Some(TOKEN))).0, 405);
+    assert_eq!(handle(&control, TOKEN, &request("POST", "/status", Some(TOKEN))).0, 404);
+    assert!(!control.take_run_request());
+}
+
+#[test]
+fn cancel_scan() {
+    let control = Control::default();
+    let (status, body) = handle(&control, TOKEN, &request("POST", "/cancel", Some(TOKEN)));
+    assert_eq!(status, 409);
+    assert_eq!(body, r#"{"error":"no scan in progress"}"#);
+
+    let run = control.start_scan();
+    assert!(control.is_scanning());
+    assert_eq!(handle(&control, TOKEN, &request("POST", "/cancel", Some(TOKEN))).0, 202);
+    assert!(!run.load(Ordering::Relaxed));
+    control.finish_scan();
+    assert!(!control.is_scanning());
+
+    // A new scan starts with a fresh flag
+    assert!(control.start_scan().load(Ordering::Relaxed));
+}
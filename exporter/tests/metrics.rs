@@ -0,0 +1,86 @@
+use simply_kaspa_utxo_exporter::metrics::concentration::{calculate, Concentration};
+use simply_kaspa_utxo_exporter::metrics::lorenz::sample;
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+}
+
+fn percentiles(c: &Concentration) -> [u64; 4] {
+    [c.p50, c.p90, c.p99, c.p999]
+}
+
+#[test]
+fn concentration_without_supply() {
+    for balances in [&[][..], &[0, 0, 0]] {
+        let c = calculate(balances);
+        assert_close(c.gini, 0.0);
+        assert_close(c.hhi, 0.0);
+        assert_eq!((c.scripts_50_percent, c.scripts_90_percent), (0, 0));
+        assert_eq!(percentiles(&c), [0; 4]);
+    }
+}
+
+#[test]
+fn concentration_of_a_single_script() {
+    let c = calculate(&[100]);
+    assert_close(c.gini, 0.0);
+    assert_close(c.hhi, 1.0);
+    assert_eq!((c.scripts_50_percent, c.scripts_90_percent), (1, 1));
+    assert_eq!(percentiles(&c), [100; 4]);
+}
+
+#[test]
+fn concentration_of_equal_balances() {
+    let c = calculate(&[5, 5, 5, 5]);
+    assert_close(c.gini, 0.0);
+    assert_close(c.hhi, 0.25);
+    // Exactly half of the supply is enough
+    assert_eq!((c.scripts_50_percent, c.scripts_90_percent), (2, 4));
+    assert_eq!(percentiles(&c), [5; 4]);
+}
+
+#[test]
+fn concentration_in_one_of_many_scripts() {
+    let c = calculate(&[0, 0, 0, 100]);
+    // The maximum for n scripts is (n - 1) / n
+    assert_close(c.gini, 0.75);
+    assert_close(c.hhi, 1.0);
+    assert_eq!((c.scripts_50_percent, c.scripts_90_percent), (1, 1));
+    assert_eq!(percentiles(&c), [0, 100, 100, 100]);
+}
+
+#[test]
+fn nearest_rank_percentiles() {
+    let balances: Vec<u64> = (1..=1_000).collect();
+    let c = calculate(&balances);
+    assert_eq!(percentiles(&c), [500, 900, 990, 999]);
+    // The largest 294 scripts hold 50.1% and the largest 685 hold 90.1% of the 500_500 total
+    assert_eq!((c.scripts_50_percent, c.scripts_90_percent), (294, 685));
+    assert_close(c.hhi, balances.iter().map(|&b| (b as f64 / 500_500.0).powi(2)).sum());
+}
+
+#[test]
+fn lorenz_curve_points() {
+    let curve = sample(&[1, 2, 3, 4], &[0.0, 50.0, 100.0]);
+    assert_eq!(curve, vec![(0.0, 0.0), (50.0, 30.0), (100.0, 100.0)]);
+}
+
+#[test]
+fn lorenz_population_shares_are_normalized() {
+    // Out of range shares are clamped, then sorted and deduplicated
+    let curve = sample(&[1, 2, 3, 4], &[150.0, -5.0, 50.0, 50.0]);
+    assert_eq!(curve, vec![(0.0, 0.0), (50.0, 30.0), (100.0, 100.0)]);
+}
+
+#[test]
+fn lorenz_rounds_to_the_nearest_script() {
+    let curve = sample(&[1, 1, 1], &[50.0]);
+    assert_eq!(curve.len(), 1);
+    assert_close(curve[0].1, 200.0 / 3.0);
+}
+
+#[test]
+fn lorenz_without_supply() {
+    assert_eq!(sample(&[], &[50.0, 100.0]), vec![(50.0, 0.0), (100.0, 0.0)]);
+    assert_eq!(sample(&[0, 0], &[50.0, 100.0]), vec![(50.0, 0.0), (100.0, 0.0)]);
+}
@@ -0,0 +1,94 @@
+use kaspa_addresses::{Address, Prefix, Version};
+use simply_kaspa_utxo_exporter::consensus::sync::RunSkipped;
+use simply_kaspa_utxo_exporter::rpc::node::{address_balances, NodeInfo, NodeRpc, ADDRESS_BATCH_SIZE};
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Mutex;
+
+/// Answers from memory and records the size of each GetUtxosByAddresses request
+struct MockNode {
+    network_id: String,
+    is_synced: bool,
+    has_utxo_index: bool,
+    /// UTXO amounts per address
+    utxos: HashMap<String, Vec<u64>>,
+    batches: Mutex<Vec<usize>>,
+}
+
+impl MockNode {
+    fn new(utxos: HashMap<String, Vec<u64>>) -> Self {
+        MockNode { network_id: "mainnet".to_string(), is_synced: true, has_utxo_index: true, utxos, batches: Mutex::new(vec![]) }
+    }
+}
+
+impl NodeRpc for MockNode {
+    async fn node_info(&self) -> Result<NodeInfo, Box<dyn Error>> {
+        Ok(NodeInfo {
+            network_id: self.network_id.clone(),
+            is_synced: self.is_synced,
+            has_utxo_index: self.has_utxo_index,
+            virtual_daa_score: 1_000,
+        })
+    }
+
+    async fn utxos_by_addresses(&self, addresses: &[Address]) -> Result<Vec<(String, u64)>, Box<dyn Error>> {
+        self.batches.lock().unwrap().push(addresses.len());
+        let mut utxos = vec![];
+        for address in addresses.iter().map(|a| a.to_string()) {
+            utxos.extend(self.utxos.get(&address).into_iter().flatten().map(|&amount| (address.clone(), amount)));
+        }
+        Ok(utxos)
+    }
+}
+
+fn address(n: u32) -> String {
+    let mut payload = [0u8; 32];
+    payload[..4].copy_from_slice(&n.to_le_bytes());
+    Address::new(Prefix::Mainnet, Version::PubKey, &payload).to_string()
+}
+
+#[tokio::test]
+async fn balances_across_batches() {
+    let addresses: Vec<String> = (0..2 * ADDRESS_BATCH_SIZE as u32 + 500).map(address).collect();
+    let node = MockNode::new(HashMap::from([(addresses[0].clone(), vec![5, 7]), (addresses[2_400].clone(), vec![11])]));
+    let (virtual_daa_score, balances) = address_balances(&node, "mainnet", &addresses).await.unwrap();
+    assert_eq!(virtual_daa_score, 1_000);
+    assert_eq!(*node.batches.lock().unwrap(), [ADDRESS_BATCH_SIZE, ADDRESS_BATCH_SIZE, 500]);
+    assert_eq!(balances.len(), addresses.len());
+    assert_eq!(balances[&addresses[0]], (12, 2));
+    assert_eq!(balances[&addresses[2_400]], (11, 1));
+    assert_eq!(balances[&addresses[1]], (0, 0));
+}
+
+#[tokio::test]
+async fn empty_address_set() {
+    let node = MockNode::new(HashMap::new());
+    let (_, balances) = address_balances(&node, "mainnet", &[]).await.unwrap();
+    assert!(balances.is_empty());
+    assert!(node.batches.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn network_mismatch() {
+    let node = MockNode { network_id: "testnet-10".to_string(), ..MockNode::new(HashMap::new()) };
+    let e = address_balances(&node, "mainnet", &[address(1)]).await.unwrap_err();
+    assert_eq!(e.to_string(), "Node is on network testnet-10, expected mainnet");
+    assert!(node.batches.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn requires_utxo_index() {
+    let node = MockNode { has_utxo_index: false, ..MockNode::new(HashMap::new()) };
+    let e = address_balances(&node, "mainnet", &[address(1)]).await.unwrap_err();
+    assert!(e.to_string().contains("--utxoindex"));
+    assert!(node.batches.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn skips_when_not_synced() {
+    let node = MockNode { is_synced: false, ..MockNode::new(HashMap::new()) };
+    let e = address_balances(&node, "mainnet", &[address(1)]).await.unwrap_err();
+    let skipped = e.downcast_ref::<RunSkipped>().expect("not synced should skip the run");
+    assert_eq!(skipped.virtual_daa_score, 1_000);
+    assert!(node.batches.lock().unwrap().is_empty());
+}
@@ -0,0 +1,112 @@
+use chrono::{DateTime, TimeZone, Utc};
+use simply_kaspa_utxo_exporter::schedule::cron::CronSchedule;
+use simply_kaspa_utxo_exporter::schedule::scheduler::{Schedule, Scheduler};
+use simply_kaspa_utxo_exporter_cli::cli_args::CatchUp;
+
+fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32, s: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(y, mo, d, h, mi, s).unwrap()
+}
+
+fn next(expression: &str, time: DateTime<Utc>) -> DateTime<Utc> {
+    expression.parse::<CronSchedule>().unwrap().next_after(time).unwrap()
+}
+
+#[test]
+fn cron_next_after() {
+    assert_eq!(next("0 * * * *", at(2025, 3, 1, 10, 0, 0)), at(2025, 3, 1, 11, 0, 0));
+    assert_eq!(next("0 * * * *", at(2025, 3, 1, 10, 59, 59)), at(2025, 3, 1, 11, 0, 0));
+    assert_eq!(next("*/15 * * * *", at(2025, 3, 1, 10, 7, 0)), at(2025, 3, 1, 10, 15, 0));
+    assert_eq!(next("@daily", at(2025, 12, 31, 0, 0, 1)), at(2026, 1, 1, 0, 0, 0));
+    assert_eq!(next("30 6 * * 1-5", at(2025, 3, 1, 0, 0, 0)), at(2025, 3, 3, 6, 30, 0));
+    assert_eq!(next("0 0 29 2 *", at(2025, 3, 1, 0, 0, 0)), at(2028, 2, 29, 0, 0, 0));
+    assert_eq!(next("0 12 * * 7", at(2025, 3, 1, 0, 0, 0)), at(2025, 3, 2, 12, 0, 0));
+    // Day of month or weekday when both are restricted
+    assert_eq!(next("0 0 15 * 1", at(2025, 3, 1, 0, 0, 0)), at(2025, 3, 3, 0, 0, 0));
+    assert_eq!(next("0 0 */10 * *", at(2025, 3, 1, 0, 0, 0)), at(2025, 3, 11, 0, 0, 0));
+}
+
+#[test]
+fn invalid_cron_expressions() {
+    for s in [
+        "",
+        "* * * *",
+        "* * * * * *",
+        "60 * * * *",
+        "* 24 * * *",
+        "* * 0 * *",
+        "* * * 13 *",
+        "* * * * 8",
+        "*/0 * * * *",
+        "5-1 * * * *",
+        "0 0 30 2 *",
+        "x * * * *",
+    ] {
+        assert!(s.parse::<CronSchedule>().is_err(), "'{s}' should be rejected");
+    }
+}
+
+#[test]
+fn aligned_interval() {
+    let schedule = Schedule::new(60, true, None).unwrap();
+    assert_eq!(schedule.next_after(at(2025, 3, 1, 10, 17, 3)), Some(at(2025, 3, 1, 11, 0, 0)));
+    assert_eq!(schedule.next_after(at(2025, 3, 1, 11, 0, 0)), Some(at(2025, 3, 1, 12, 0, 0)));
+    let daily = Schedule::new(1440, true, None).unwrap();
+    assert_eq!(daily.next_after(at(2025, 3, 1, 10, 17, 3)), Some(at(2025, 3, 2, 0, 0, 0)));
+    assert!(Schedule::new(0, true, None).is_err());
+    assert!(Schedule::new(0, false, None).unwrap().is_oneshot());
+}
+
+#[test]
+fn aligned_runs_use_the_scheduled_time() {
+    let schedule = Schedule::new(60, true, None).unwrap();
+    let mut scheduler = Scheduler::new(schedule, CatchUp::Once, Some(at(2025, 3, 1, 10, 0, 0)), at(2025, 3, 1, 10, 20, 0));
+    assert_eq!(scheduler.due(at(2025, 3, 1, 10, 59, 59)), None);
+    assert_eq!(scheduler.due(at(2025, 3, 1, 11, 0, 2)), Some(at(2025, 3, 1, 11, 0, 0)));
+    scheduler.completed(at(2025, 3, 1, 11, 0, 0), at(2025, 3, 1, 11, 5, 0));
+    assert_eq!(scheduler.next_run(), at(2025, 3, 1, 12, 0, 0));
+}
+
+#[test]
+fn catch_up_after_downtime() {
+    let last_run = Some(at(2025, 3, 1, 10, 0, 0));
+    let now = at(2025, 3, 1, 13, 30, 0);
+
+    let once = Scheduler::new(Schedule::new(60, true, None).unwrap(), CatchUp::Once, last_run, now);
+    assert_eq!(once.due(now), Some(now));
+    let skip = Scheduler::new(Schedule::new(60, true, None).unwrap(), CatchUp::Skip, last_run, now);
+    assert_eq!(skip.due(now), None);
+    assert_eq!(skip.next_run(), at(2025, 3, 1, 14, 0, 0));
+
+    // A run overlapping the next scheduled time
+    let mut scheduler = Scheduler::new(Schedule::new(60, true, None).unwrap(), CatchUp::Once, last_run, at(2025, 3, 1, 10, 1, 0));
+    scheduler.completed(at(2025, 3, 1, 11, 0, 0), at(2025, 3, 1, 12, 10, 0));
+    assert_eq!(scheduler.due(at(2025, 3, 1, 12, 10, 3)), Some(at(2025, 3, 1, 12, 10, 3)));
+}
+
+#[test]
+fn interval_runs() {
+    let schedule = Schedule::new(60, false, None).unwrap();
+    let first = Scheduler::new(schedule.clone(), CatchUp::Once, None, at(2025, 3, 1, 10, 17, 0));
+    assert_eq!(first.due(at(2025, 3, 1, 10, 17, 0)), Some(at(2025, 3, 1, 10, 17, 0)));
+    let mut scheduler = Scheduler::new(schedule, CatchUp::Once, Some(at(2025, 3, 1, 10, 17, 0)), at(2025, 3, 1, 10, 20, 0));
+    assert_eq!(scheduler.due(at(2025, 3, 1, 11, 16, 59)), None);
+    assert_eq!(scheduler.due(at(2025, 3, 1, 11, 17, 2)), Some(at(2025, 3, 1, 11, 17, 2)));
+    scheduler.completed(at(2025, 3, 1, 11, 17, 2), at(2025, 3, 1, 11, 20, 0));
+    assert_eq!(scheduler.next_run(), at(2025, 3, 1, 12, 17, 2));
+}
+
+#[test]
+fn reschedule_from_the_last_run() {
+    let mut scheduler = Scheduler::new(
+        Schedule::new(60, false, None).unwrap(),
+        CatchUp::Once,
+        Some(at(2025, 3, 1, 10, 17, 0)),
+        at(2025, 3, 1, 10, 20, 0),
+    );
+    scheduler.reschedule(Schedule::new(60, true, None).unwrap(), CatchUp::Once, at(2025, 3, 1, 10, 30, 0));
+    assert_eq!(scheduler.next_run(), at(2025, 3, 1, 11, 0, 0));
+    scheduler.reschedule(Schedule::new(0, false, Some("0 0 * * *")).unwrap(), CatchUp::Skip, at(2025, 3, 1, 10, 30, 0));
+    assert_eq!(scheduler.next_run(), at(2025, 3, 2, 0, 0, 0));
+    scheduler.reschedule(Schedule::new(5, false, None).unwrap(), CatchUp::Once, at(2025, 3, 1, 10, 30, 0));
+    assert_eq!(scheduler.due(at(2025, 3, 1, 10, 30, 0)), Some(at(2025, 3, 1, 10, 30, 0)));
+}
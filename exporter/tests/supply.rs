@@ -0,0 +1,50 @@
+use kaspa_consensus_core::config::params::MAINNET_PARAMS;
+use simply_kaspa_utxo_exporter::metrics::supply::{expected_supply, network_supply};
+
+const SECONDS_PER_MONTH: u64 = 2_629_800;
+
+#[test]
+fn matches_the_naive_sum() {
+    // The subsidy steps up at the phase boundary 1_500, which galloping alone would skip over
+    let subsidy = |d: u64| {
+        if d < 1_000 {
+            500
+        } else if d < 1_500 {
+            440 >> ((d - 1_000) / 111)
+        } else {
+            50 >> ((d - 1_500) / 333).min(5)
+        }
+    };
+    for n in [0u64, 1, 2, 999, 1_000, 1_001, 1_499, 1_500, 1_777, 5_000, 12_345] {
+        let naive: u128 = (0..n).map(|d| subsidy(d) as u128).sum();
+        assert_eq!(expected_supply(subsidy, &[1_000, 1_500], n), naive, "n={n}");
+    }
+    // Boundaries outside the range are ignored
+    assert_eq!(expected_supply(|_| 7, &[0, 20, u64::MAX], 10), 70);
+}
+
+#[test]
+fn mainnet_pre_deflationary_supply() {
+    let deflationary_phase_daa_score = MAINNET_PARAMS.deflationary_phase_daa_score;
+    assert_eq!(deflationary_phase_daa_score, 15_519_600);
+    // 500 KAS per block until the deflationary phase
+    assert_eq!(network_supply(&MAINNET_PARAMS, deflationary_phase_daa_score), 775_980_000_000_000_000);
+    // 440 KAS per second during the first deflationary month at 1 BPS
+    let first_month = network_supply(&MAINNET_PARAMS, deflationary_phase_daa_score + SECONDS_PER_MONTH)
+        - network_supply(&MAINNET_PARAMS, deflationary_phase_daa_score);
+    assert_eq!(first_month, SECONDS_PER_MONTH as u128 * 44_000_000_000);
+}
+
+#[test]
+fn mainnet_subsidy_across_the_bps_activation() {
+    let bps = MAINNET_PARAMS.bps();
+    let activation = bps.activation().daa_score();
+    let subsidy = |daa_score: u64| network_supply(&MAINNET_PARAMS, daa_score + 1) - network_supply(&MAINNET_PARAMS, daa_score);
+    let before = subsidy(activation - 1);
+    let after = subsidy(activation);
+    assert_eq!(after, before.div_ceil((bps.after() / bps.before()) as u128));
+    assert_eq!(
+        network_supply(&MAINNET_PARAMS, activation + 1_000) - network_supply(&MAINNET_PARAMS, activation - 1_000),
+        1_000 * before + 1_000 * after
+    );
+}
@@ -0,0 +1,120 @@
+use serde_json::Value;
+use simply_kaspa_utxo_exporter::alerts::rules::Alert;
+use simply_kaspa_utxo_exporter::alerts::webhook::WebhookNotifier;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// A local HTTP stand-in answering with the given statuses in turn (the last one repeats), returns its url and the received bodies
+async fn stand_in(statuses: Vec<u16>) -> (String, Arc<Mutex<Vec<Value>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let bodies = Arc::new(Mutex::new(vec![]));
+    tokio::spawn({
+        let bodies = bodies.clone();
+        async move {
+            for request in 0.. {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let body = read_body(&mut stream).await;
+                bodies.lock().unwrap().push(body);
+                let status = statuses[request.min(statuses.len() - 1)];
+                let response = format!("HTTP/1.1 {status} Status\r\ncontent-length: 0\r\nconnection: close\r\n\r\n");
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        }
+    });
+    (url, bodies)
+}
+
+async fn read_body(stream: &mut TcpStream) -> Value {
+    let mut request = vec![];
+    let mut chunk = [0u8; 1024];
+    loop {
+        let n = stream.read(&mut chunk).await.unwrap();
+        request.extend_from_slice(&chunk[..n]);
+        let text = String::from_utf8_lossy(&request);
+        if let Some((head, body)) = text.split_once("\r\n\r\n") {
+            let length = head
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                .map(|(_, value)| value.trim().parse::<usize>().unwrap())
+                .unwrap_or(0);
+            if body.len() >= length {
+                return serde_json::from_str(body).unwrap();
+            }
+        }
+        assert!(n > 0, "connection closed before the body was received");
+    }
+}
+
+fn notifier(url: &str, retry_count: u16, dead_letter_file: &PathBuf) -> WebhookNotifier {
+    let _ = fs::remove_file(dead_letter_file);
+    WebhookNotifier::new(
+        vec![url.to_string()],
+        "mainnet".to_string(),
+        retry_count,
+        Duration::from_millis(10),
+        Some(dead_letter_file.to_str().unwrap().to_string()),
+        Arc::new(AtomicBool::new(true)),
+    )
+    .unwrap()
+}
+
+fn dead_letter_file(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("webhook-{name}-{}.jsonl", std::process::id()))
+}
+
+fn alert(rank: i32) -> Alert {
+    Alert::TopScriptEntered { timestamp: 1_000, script_public_key: "ab".to_string(), address: None, rank, amount: 5_000 }
+}
+
+#[tokio::test]
+async fn delivers_alerts() {
+    let (url, bodies) = stand_in(vec![200]).await;
+    let dead_letters = dead_letter_file("delivers");
+    let notifier = notifier(&url, 2, &dead_letters);
+    notifier.notify(vec![alert(1), alert(2)]);
+    notifier.close().await;
+
+    let bodies = bodies.lock().unwrap();
+    assert_eq!(bodies.len(), 2);
+    assert_eq!(bodies[0]["network"], "mainnet");
+    assert_eq!(bodies[0]["alert"]["type"], "top_script_entered");
+    assert_eq!(bodies[1]["alert"]["rank"], 2);
+    assert!(!dead_letters.exists());
+}
+
+#[tokio::test]
+async fn retries_until_delivered() {
+    let (url, bodies) = stand_in(vec![500, 503, 200]).await;
+    let dead_letters = dead_letter_file("retries");
+    let notifier = notifier(&url, 3, &dead_letters);
+    notifier.notify(vec![alert(1)]);
+    notifier.close().await;
+
+    assert_eq!(bodies.lock().unwrap().len(), 3);
+    assert!(!dead_letters.exists());
+}
+
+#[tokio::test]
+async fn dead_letters_after_the_last_retry() {
+    let (url, bodies) = stand_in(vec![500]).await;
+    let dead_letters = dead_letter_file("dead-letters");
+    let notifier = notifier(&url, 2, &dead_letters);
+    notifier.notify(vec![alert(1)]);
+    notifier.close().await;
+
+    assert_eq!(bodies.lock().unwrap().len(), 3);
+    let entries: Vec<Value> =
+        fs::read_to_string(&dead_letters).unwrap().lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    fs::remove_file(&dead_letters).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["url"], url.as_str());
+    assert!(entries[0]["error"].as_str().unwrap().contains("500"));
+    assert_eq!(entries[0]["payload"]["alert"]["type"], "top_script_entered");
+}
@@ -0,0 +1,148 @@
+use crate::metrics::tiers::Tiers;
+use crate::metrics::top::TopN;
+use crate::scanner::ScanCancelled;
+use crate::tier_scheme::TierScheme;
+use kaspa_consensus_core::tx::ScriptPublicKey;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// What to aggregate the per script balances into.
+pub struct AggregationOptions {
+    pub tier_schemes: Vec<TierScheme>,
+    /// Number of top scripts and entities to rank (0 = all)
+    pub top_scripts_count: usize,
+    /// Scripts and entities below this amount (in sompi) are not ranked
+    pub top_scripts_min_amount: u64,
+    /// Number of exclusion rules `ScriptClass::exclusion` indexes into
+    pub exclusion_count: usize,
+    /// Group the scripts by entity, for the entity tiers and top entities
+    pub entities: bool,
+}
+
+/// How a script is aggregated, resolved by the caller from its exclusion rules and labels
+#[derive(Default)]
+pub struct ScriptClass<'a> {
+    /// Index of the exclusion rule matching the script, excluded scripts are left out of the tiers and balances
+    pub exclusion: Option<usize>,
+    /// Entity and category the script is labeled with. The scripts of an entity are expected to agree on the category,
+    /// scripts without one take it from the others
+    pub entity: Option<(&'a str, Option<&'a str>)>,
+}
+
+#[derive(Default)]
+pub struct EntityBalance {
+    pub category: Option<String>,
+    pub amount: u64,
+    pub script_count: u64,
+    pub utxo_count: u64,
+    /// At least one of the entity's scripts is excluded
+    pub excluded: bool,
+}
+
+#[derive(Default)]
+pub struct ExcludedBalance {
+    pub script_count: u64,
+    pub utxo_count: u64,
+    pub amount: u64,
+}
+
+pub enum TopEntity {
+    Entity(String, EntityBalance),
+    /// A script without a label
+    Script(Vec<u8>),
+}
+
+pub struct Aggregation {
+    /// Per scheme, scripts that aren't excluded
+    pub tiers: Vec<Tiers>,
+    /// (amount, script), largest first
+    pub top_scripts: Vec<(u64, Vec<u8>)>,
+    /// Balances of the scripts that aren't excluded, ascending
+    pub balances: Vec<u64>,
+    /// Per exclusion rule
+    pub excluded: Vec<ExcludedBalance>,
+    /// Per scheme, entities and unlabeled scripts that aren't excluded, empty unless grouping by entity
+    pub entity_tiers: Vec<Tiers>,
+    /// Entities and unlabeled scripts, largest first
+    pub top_entities: Vec<(u64, TopEntity)>,
+}
+
+/// Aggregates the per script (amount, utxo count) into tiers, top scripts and entities.
+/// Ties are ranked by the larger script or entity name, so the result doesn't depend on the map order.
+pub fn aggregate<'a>(
+    script_amounts: &HashMap<ScriptPublicKey, (u64, u64)>,
+    options: &AggregationOptions,
+    classify: impl Fn(&ScriptPublicKey) -> ScriptClass<'a>,
+    run: &AtomicBool,
+) -> Result<Aggregation, ScanCancelled> {
+    let count = if options.top_scripts_count == 0 { usize::MAX } else { options.top_scripts_count };
+    let mut tiers: Vec<Tiers> = options.tier_schemes.iter().cloned().map(Tiers::new).collect();
+    let mut top_scripts: TopN<Vec<u8>> = TopN::new(count, 1_000_000);
+    let mut balances = Vec::with_capacity(script_amounts.len());
+    let mut excluded: Vec<ExcludedBalance> = (0..options.exclusion_count).map(|_| ExcludedBalance::default()).collect();
+    let mut entity_tiers: Vec<Tiers> = vec![];
+    let mut entities: HashMap<&'a str, EntityBalance> = HashMap::new();
+    let mut unlabeled: TopN<Vec<u8>> = TopN::new(if options.entities { count } else { 0 }, 0);
+    if options.entities {
+        entity_tiers = options.tier_schemes.iter().cloned().map(Tiers::new).collect();
+    }
+
+    for (script, &(amount, utxo_count)) in script_amounts.iter() {
+        let class = classify(script);
+        if let Some(idx) = class.exclusion {
+            excluded[idx].script_count += 1;
+            excluded[idx].utxo_count += utxo_count;
+            excluded[idx].amount += amount;
+        } else {
+            balances.push(amount);
+            tiers.iter_mut().for_each(|t| t.add(amount, utxo_count));
+        }
+        if options.entities {
+            if let Some((name, category)) = class.entity {
+                let entity = entities.entry(name).or_default();
+                entity.category = entity.category.take().or_else(|| category.map(str::to_string));
+                entity.amount += amount;
+                entity.script_count += 1;
+                entity.utxo_count += utxo_count;
+                entity.excluded |= class.exclusion.is_some();
+            } else {
+                if class.exclusion.is_none() {
+                    entity_tiers.iter_mut().for_each(|t| t.add(amount, utxo_count));
+                }
+                if amount >= options.top_scripts_min_amount {
+                    unlabeled.push(amount, || script.script().to_vec());
+                }
+            }
+        }
+        if amount >= options.top_scripts_min_amount {
+            top_scripts.push(amount, || script.script().to_vec());
+        }
+        if !run.load(Ordering::Relaxed) {
+            return Err(ScanCancelled);
+        }
+    }
+    balances.sort_unstable();
+
+    let mut top_entities: Vec<(u64, TopEntity)> = Vec::with_capacity(entities.len() + unlabeled.len());
+    for (name, entity) in entities {
+        if !entity.excluded {
+            entity_tiers.iter_mut().for_each(|t| t.add(entity.amount, entity.utxo_count));
+        }
+        if entity.amount >= options.top_scripts_min_amount {
+            top_entities.push((entity.amount, TopEntity::Entity(name.to_string(), entity)));
+        }
+    }
+    top_entities.extend(unlabeled.into_sorted_vec().into_iter().map(|(amount, script)| (amount, TopEntity::Script(script))));
+    top_entities.sort_by(|(a, x), (b, y)| b.cmp(a).then_with(|| tie_key(y).cmp(&tie_key(x))));
+    top_entities.truncate(count);
+
+    Ok(Aggregation { tiers, top_scripts: top_scripts.into_sorted_vec(), balances, excluded, entity_tiers, top_entities })
+}
+
+/// Entities rank before unlabeled scripts of the same amount
+fn tie_key(entity: &TopEntity) -> (bool, &[u8]) {
+    match entity {
+        TopEntity::Entity(name, _) => (true, name.as_bytes()),
+        TopEntity::Script(script) => (false, script.as_slice()),
+    }
+}
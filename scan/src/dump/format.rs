@@ -0,0 +1,242 @@
+// Dump file layout, all integers little endian:
+// - magic, format version (u16) and the header, uncompressed
+// - gzip compressed records, each utxo prefixed with UTXO_TAG
+// - END_TAG, utxo count (u64), total amount (u64) and a crc32 of the header and utxo records
+
+use crc32fast::Hasher;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+pub const MAGIC: &[u8; 8] = b"KASUTXOS";
+pub const VERSION: u16 = 1;
+const UTXO_TAG: u8 = 1;
+const END_TAG: u8 = 0;
+/// Consensus limit of a script public key, longer scripts are rejected before allocating them
+pub const MAX_SCRIPT_LEN: usize = 10_000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DumpHeader {
+    pub network: String,
+    pub anchor_mode: String,
+    pub anchor_hash: [u8; 32],
+    pub daa_score: u64,
+    pub created_ms: u64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UtxoRecord {
+    pub transaction_id: [u8; 32],
+    pub index: u32,
+    pub amount: u64,
+    pub block_daa_score: u64,
+    pub is_coinbase: bool,
+    pub script_version: u16,
+    pub script: Vec<u8>,
+}
+
+pub struct DumpWriter<W: Write> {
+    encoder: GzEncoder<W>,
+    hasher: Hasher,
+    utxo_count: u64,
+    total_amount: u64,
+}
+
+impl DumpWriter<BufWriter<File>> {
+    pub fn create(path: impl AsRef<Path>, header: &DumpHeader) -> io::Result<Self> {
+        DumpWriter::new(BufWriter::new(File::create(path)?), header)
+    }
+}
+
+impl<W: Write> DumpWriter<W> {
+    pub fn new(mut inner: W, header: &DumpHeader) -> io::Result<Self> {
+        let mut hasher = Hasher::new();
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        write_string(&mut bytes, &header.network)?;
+        write_string(&mut bytes, &header.anchor_mode)?;
+        bytes.extend_from_slice(&header.anchor_hash);
+        bytes.extend_from_slice(&header.daa_score.to_le_bytes());
+        bytes.extend_from_slice(&header.created_ms.to_le_bytes());
+        hasher.update(&bytes);
+        inner.write_all(&bytes)?;
+        Ok(DumpWriter { encoder: GzEncoder::new(inner, Compression::default()), hasher, utxo_count: 0, total_amount: 0 })
+    }
+
+    pub fn write(&mut self, utxo: &UtxoRecord) -> io::Result<()> {
+        if utxo.script.len() > MAX_SCRIPT_LEN {
+            return Err(invalid_data(&format!("Script of {} bytes exceeds {MAX_SCRIPT_LEN}", utxo.script.len())));
+        }
+        let script_len = utxo.script.len() as u32;
+        let mut bytes = Vec::with_capacity(64 + utxo.script.len());
+        bytes.push(UTXO_TAG);
+        bytes.extend_from_slice(&utxo.transaction_id);
+        bytes.extend_from_slice(&utxo.index.to_le_bytes());
+        bytes.extend_from_slice(&utxo.amount.to_le_bytes());
+        bytes.extend_from_slice(&utxo.block_daa_score.to_le_bytes());
+        bytes.push(utxo.is_coinbase as u8);
+        bytes.extend_from_slice(&utxo.script_version.to_le_bytes());
+        bytes.extend_from_slice(&script_len.to_le_bytes());
+        bytes.extend_from_slice(&utxo.script);
+        self.hasher.update(&bytes);
+        self.encoder.write_all(&bytes)?;
+        self.utxo_count += 1;
+        self.total_amount += utxo.amount;
+        Ok(())
+    }
+
+    pub fn utxo_count(&self) -> u64 {
+        self.utxo_count
+    }
+
+    pub fn total_amount(&self) -> u64 {
+        self.total_amount
+    }
+
+    /// Writes the trailer and returns the utxo count
+    pub fn finish(mut self) -> io::Result<u64> {
+        self.encoder.write_all(&[END_TAG])?;
+        self.encoder.write_all(&self.utxo_count.to_le_bytes())?;
+        self.encoder.write_all(&self.total_amount.to_le_bytes())?;
+        self.encoder.write_all(&self.hasher.finalize().to_le_bytes())?;
+        self.encoder.finish()?.flush()?;
+        Ok(self.utxo_count)
+    }
+}
+
+pub struct DumpReader<R: Read> {
+    header: DumpHeader,
+    decoder: BufReader<GzDecoder<R>>,
+    hasher: Hasher,
+    utxo_count: u64,
+    total_amount: u64,
+    done: bool,
+}
+
+impl DumpReader<BufReader<File>> {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        DumpReader::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> DumpReader<R> {
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let mut hasher = Hasher::new();
+        let magic: [u8; 8] = read_array(&mut inner, &mut hasher)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("Not a UTXO dump file"));
+        }
+        let version = u16::from_le_bytes(read_array(&mut inner, &mut hasher)?);
+        if version != VERSION {
+            return Err(invalid_data(&format!("Unsupported dump format version {version}")));
+        }
+        let header = DumpHeader {
+            network: read_string(&mut inner, &mut hasher)?,
+            anchor_mode: read_string(&mut inner, &mut hasher)?,
+            anchor_hash: read_array(&mut inner, &mut hasher)?,
+            daa_score: u64::from_le_bytes(read_array(&mut inner, &mut hasher)?),
+            created_ms: u64::from_le_bytes(read_array(&mut inner, &mut hasher)?),
+        };
+        Ok(DumpReader { header, decoder: BufReader::new(GzDecoder::new(inner)), hasher, utxo_count: 0, total_amount: 0, done: false })
+    }
+
+    pub fn header(&self) -> &DumpHeader {
+        &self.header
+    }
+
+    fn read_record(&mut self) -> io::Result<Option<UtxoRecord>> {
+        let mut tag = [0u8; 1];
+        self.decoder.read_exact(&mut tag)?;
+        match tag[0] {
+            UTXO_TAG => {
+                self.hasher.update(&tag);
+                let transaction_id = read_array(&mut self.decoder, &mut self.hasher)?;
+                let index = u32::from_le_bytes(read_array(&mut self.decoder, &mut self.hasher)?);
+                let amount = u64::from_le_bytes(read_array(&mut self.decoder, &mut self.hasher)?);
+                let block_daa_score = u64::from_le_bytes(read_array(&mut self.decoder, &mut self.hasher)?);
+                let [is_coinbase] = read_array(&mut self.decoder, &mut self.hasher)?;
+                let script_version = u16::from_le_bytes(read_array(&mut self.decoder, &mut self.hasher)?);
+                let script_len = u32::from_le_bytes(read_array(&mut self.decoder, &mut self.hasher)?);
+                if script_len as usize > MAX_SCRIPT_LEN {
+                    return Err(invalid_data(&format!("Script of {script_len} bytes exceeds {MAX_SCRIPT_LEN}")));
+                }
+                let mut script = vec![0u8; script_len as usize];
+                self.decoder.read_exact(&mut script)?;
+                self.hasher.update(&script);
+                self.utxo_count += 1;
+                self.total_amount += amount;
+                Ok(Some(UtxoRecord {
+                    transaction_id,
+                    index,
+                    amount,
+                    block_daa_score,
+                    is_coinbase: is_coinbase != 0,
+                    script_version,
+                    script,
+                }))
+            }
+            END_TAG => {
+                let checksum = self.hasher.clone().finalize();
+                let mut trailer = Hasher::new();
+                let utxo_count = u64::from_le_bytes(read_array(&mut self.decoder, &mut trailer)?);
+                let total_amount = u64::from_le_bytes(read_array(&mut self.decoder, &mut trailer)?);
+                let expected_checksum = u32::from_le_bytes(read_array(&mut self.decoder, &mut trailer)?);
+                if utxo_count != self.utxo_count || total_amount != self.total_amount {
+                    return Err(invalid_data(&format!(
+                        "Dump trailer mismatch, expected {utxo_count} utxos totaling {total_amount}, read {} totaling {}",
+                        self.utxo_count, self.total_amount
+                    )));
+                }
+                if checksum != expected_checksum {
+                    return Err(invalid_data(&format!(
+                        "Dump checksum mismatch, expected {expected_checksum:08x}, got {checksum:08x}"
+                    )));
+                }
+                Ok(None)
+            }
+            other => Err(invalid_data(&format!("Unknown record tag {other}"))),
+        }
+    }
+}
+
+impl<R: Read> Iterator for DumpReader<R> {
+    type Item = io::Result<UtxoRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.read_record().transpose();
+        self.done = !matches!(result, Some(Ok(_)));
+        result
+    }
+}
+
+fn write_string(bytes: &mut Vec<u8>, value: &str) -> io::Result<()> {
+    let len = u8::try_from(value.len()).map_err(|_| invalid_data("Header field too long"))?;
+    bytes.push(len);
+    bytes.extend_from_slice(value.as_bytes());
+    Ok(())
+}
+
+fn read_string(reader: &mut impl Read, hasher: &mut Hasher) -> io::Result<String> {
+    let [len] = read_array(reader, hasher)?;
+    let mut bytes = vec![0u8; len as usize];
+    reader.read_exact(&mut bytes)?;
+    hasher.update(&bytes);
+    String::from_utf8(bytes).map_err(|_| invalid_data("Invalid header string"))
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read, hasher: &mut Hasher) -> io::Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    reader.read_exact(&mut bytes)?;
+    hasher.update(&bytes);
+    Ok(bytes)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
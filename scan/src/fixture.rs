@@ -0,0 +1,173 @@
+use crate::dump::format::{DumpHeader, DumpWriter};
+use crate::source::dump_file::record_from_utxo;
+use crate::source::memory::MemorySource;
+use crate::source::{SourceAnchor, UtxoIterator, UtxoSource};
+use kaspa_consensus_core::tx::{ScriptPublicKey, TransactionId, TransactionOutpoint, UtxoEntry};
+use std::error::Error;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Deterministic UTXO sets with known contents, for tests and benchmarks.
+/// Outpoints are derived from the insertion order, so equal fixtures yield equal sets.
+pub struct Fixture {
+    anchor: SourceAnchor,
+    utxos: Vec<(TransactionOutpoint, Arc<UtxoEntry>)>,
+}
+
+impl Fixture {
+    pub fn new(daa_score: u64) -> Self {
+        Fixture { anchor: SourceAnchor { mode: "virtual".to_string(), block_hash: [0xab; 32], daa_score }, utxos: vec![] }
+    }
+
+    pub fn add(&mut self, script: &ScriptPublicKey, amount: u64, block_daa_score: u64) -> &mut Self {
+        self.add_utxo(script, amount, block_daa_score, false)
+    }
+
+    pub fn add_coinbase(&mut self, script: &ScriptPublicKey, amount: u64, block_daa_score: u64) -> &mut Self {
+        self.add_utxo(script, amount, block_daa_score, true)
+    }
+
+    /// Adds `count` UTXOs of the same amount
+    pub fn add_many(&mut self, script: &ScriptPublicKey, amount: u64, count: usize, block_daa_score: u64) -> &mut Self {
+        for _ in 0..count {
+            self.add_utxo(script, amount, block_daa_score, false);
+        }
+        self
+    }
+
+    fn add_utxo(&mut self, script: &ScriptPublicKey, amount: u64, block_daa_score: u64, is_coinbase: bool) -> &mut Self {
+        let outpoint = outpoint(self.utxos.len() as u64);
+        self.utxos.push((outpoint, Arc::new(UtxoEntry::new(amount, script.clone(), block_daa_score, is_coinbase))));
+        self
+    }
+
+    pub fn anchor(&self) -> &SourceAnchor {
+        &self.anchor
+    }
+
+    pub fn utxos(&self) -> &[(TransactionOutpoint, Arc<UtxoEntry>)] {
+        &self.utxos
+    }
+
+    pub fn source(&self) -> MemorySource {
+        MemorySource::new(self.anchor.clone(), self.utxos.clone())
+    }
+
+    /// Writes the set as a dump file, to test `DumpFileSource` against the same contents
+    pub fn write_dump(&self, path: impl AsRef<Path>, network: &str) -> io::Result<u64> {
+        let header = DumpHeader {
+            network: network.to_string(),
+            anchor_mode: self.anchor.mode.clone(),
+            anchor_hash: self.anchor.block_hash,
+            daa_score: self.anchor.daa_score,
+            created_ms: 0,
+        };
+        let mut writer = DumpWriter::create(path, &header)?;
+        for (outpoint, entry) in self.utxos.iter() {
+            writer.write(&record_from_utxo(outpoint, entry))?;
+        }
+        writer.finish()
+    }
+}
+
+/// Pay-to-pubkey (schnorr) script for a pubkey filled with `seed`
+pub fn p2pk_script(seed: u8) -> ScriptPublicKey {
+    let mut script = vec![0x20];
+    script.extend_from_slice(&[seed; 32]);
+    script.push(0xac);
+    ScriptPublicKey::from_vec(0, script)
+}
+
+/// Pay-to-pubkey (ECDSA) script for a compressed pubkey filled with `seed`
+pub fn p2pk_ecdsa_script(seed: u8) -> ScriptPublicKey {
+    let mut script = vec![0x21, 0x02];
+    script.extend_from_slice(&[seed; 32]);
+    script.push(0xab);
+    ScriptPublicKey::from_vec(0, script)
+}
+
+/// Pay-to-script-hash script for a hash filled with `seed`
+pub fn p2sh_script(seed: u8) -> ScriptPublicKey {
+    let mut script = vec![0xaa, 0x20];
+    script.extend_from_slice(&[seed; 32]);
+    script.push(0x87);
+    ScriptPublicKey::from_vec(0, script)
+}
+
+/// A script which doesn't match any standard template, so it has no address
+pub fn non_standard_script(seed: u8) -> ScriptPublicKey {
+    ScriptPublicKey::from_vec(0, vec![0x51, seed, 0x75])
+}
+
+/// Shape of a generated UTXO set
+#[derive(Clone, Debug)]
+pub struct Generator {
+    pub utxo_count: u64,
+    pub script_count: u64,
+    /// 1.0 spreads the UTXOs evenly over the scripts, larger values concentrate them on fewer scripts
+    pub script_skew: f64,
+    pub seed: u64,
+}
+
+/// A large synthetic UTXO set which is generated on the fly, every pass yields the same UTXOs.
+/// Amounts are log-uniform between 1 sompi and 10M KAS, block DAA scores uniform up to the anchor.
+pub struct GeneratedSource {
+    anchor: SourceAnchor,
+    generator: Generator,
+}
+
+impl GeneratedSource {
+    pub fn new(daa_score: u64, generator: Generator) -> Self {
+        GeneratedSource { anchor: Fixture::new(daa_score).anchor, generator }
+    }
+
+    fn utxo(&self, n: u64) -> (TransactionOutpoint, Arc<UtxoEntry>) {
+        let r1 = mix(mix(self.generator.seed).wrapping_add(n));
+        let r2 = mix(r1);
+        let r3 = mix(r2);
+        let script_count = self.generator.script_count.max(1);
+        let script_index = ((unit(r1).powf(self.generator.script_skew) * script_count as f64) as u64).min(script_count - 1);
+        let amount = 10f64.powf(unit(r2) * 15.0) as u64;
+        let block_daa_score = (unit(r3) * self.anchor.daa_score as f64) as u64;
+        (outpoint(n), Arc::new(UtxoEntry::new(amount, indexed_script(script_index), block_daa_score, false)))
+    }
+}
+
+impl UtxoSource for GeneratedSource {
+    fn anchor(&self) -> &SourceAnchor {
+        &self.anchor
+    }
+
+    fn utxos(&self) -> Result<UtxoIterator<'_>, Box<dyn Error>> {
+        Ok(Box::new((0..self.generator.utxo_count).map(|n| Ok(self.utxo(n)))))
+    }
+}
+
+fn outpoint(n: u64) -> TransactionOutpoint {
+    let mut transaction_id = [0u8; 32];
+    transaction_id[..8].copy_from_slice(&n.to_le_bytes());
+    TransactionOutpoint::new(TransactionId::from_bytes(transaction_id), (n % 4) as u32)
+}
+
+/// Pay-to-pubkey script with the index as pubkey, for sets with more than 256 scripts
+pub fn indexed_script(index: u64) -> ScriptPublicKey {
+    let mut script = vec![0x20];
+    script.extend_from_slice(&index.to_le_bytes());
+    script.extend_from_slice(&[0; 24]);
+    script.push(0xac);
+    ScriptPublicKey::from_vec(0, script)
+}
+
+// splitmix64
+fn mix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// Maps to [0, 1)
+fn unit(x: u64) -> f64 {
+    (x >> 11) as f64 / (1u64 << 53) as f64
+}
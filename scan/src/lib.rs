@@ -0,0 +1,7 @@
+pub mod aggregate;
+pub mod dump;
+pub mod fixture;
+pub mod metrics;
+pub mod scanner;
+pub mod source;
+pub mod tier_scheme;
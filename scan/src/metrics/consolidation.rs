@@ -0,0 +1,114 @@
+/// Amounts kept per script for the median, the median is exact up to this many UTXOs
+pub const MEDIAN_SAMPLE_SIZE: usize = 1_024;
+
+/// Consolidation stats of a single script, gathered over its UTXOs.
+/// `count` is the number of UTXOs at or above the dust limit, which the amount stats cover.
+/// `dust_count` is the number of UTXOs below the dust limit, which are only counted.
+/// The DAA score range covers both.
+pub struct ScriptUtxos {
+    pub count: u64,
+    pub amount: u64,
+    pub min_amount: u64,
+    pub max_amount: u64,
+    pub dust_count: u64,
+    pub oldest_block_daa_score: u64,
+    pub newest_block_daa_score: u64,
+    /// Uniform sample of the amounts, only filled from the third UTXO on as min and max cover the first two
+    sample: Vec<u64>,
+}
+
+pub struct UtxoStats {
+    /// UTXOs at or above the dust limit
+    pub count: u64,
+    pub amount: u64,
+    pub min_amount: u64,
+    pub max_amount: u64,
+    /// Exact up to `MEDIAN_SAMPLE_SIZE` UTXOs, estimated from a uniform sample above
+    pub median_amount: u64,
+    /// UTXOs below the dust limit
+    pub dust_count: u64,
+    pub oldest_block_daa_score: u64,
+    pub newest_block_daa_score: u64,
+}
+
+impl Default for ScriptUtxos {
+    fn default() -> Self {
+        ScriptUtxos {
+            count: 0,
+            amount: 0,
+            min_amount: u64::MAX,
+            max_amount: 0,
+            dust_count: 0,
+            oldest_block_daa_score: u64::MAX,
+            newest_block_daa_score: 0,
+            sample: vec![],
+        }
+    }
+}
+
+impl ScriptUtxos {
+    pub fn add(&mut self, amount: u64, block_daa_score: u64, dust: bool) {
+        if dust {
+            self.dust_count += 1;
+        } else {
+            self.count += 1;
+            self.amount += amount;
+            self.sample_amount(amount);
+            self.min_amount = self.min_amount.min(amount);
+            self.max_amount = self.max_amount.max(amount);
+        }
+        self.oldest_block_daa_score = self.oldest_block_daa_score.min(block_daa_score);
+        self.newest_block_daa_score = self.newest_block_daa_score.max(block_daa_score);
+    }
+
+    /// Reservoir sampling, `count` already includes the amount
+    fn sample_amount(&mut self, amount: u64) {
+        match self.count {
+            1 | 2 => {}
+            3 => self.sample.extend([self.min_amount, self.max_amount, amount]),
+            count if count as usize <= MEDIAN_SAMPLE_SIZE => self.sample.push(amount),
+            count => {
+                // Deterministic pseudo random index, so repeated scans give the same estimate
+                let index = mix(count ^ self.amount) % count;
+                if (index as usize) < MEDIAN_SAMPLE_SIZE {
+                    self.sample[index as usize] = amount;
+                }
+            }
+        }
+    }
+
+    pub fn stats(mut self) -> UtxoStats {
+        let median_amount = match self.count {
+            0 => 0,
+            1 | 2 => self.min_amount + (self.max_amount - self.min_amount) / 2,
+            _ => {
+                let len = self.sample.len();
+                let (lower, &mut upper, _) = self.sample.select_nth_unstable(len / 2);
+                if len % 2 == 1 {
+                    upper
+                } else {
+                    let lower = *lower.iter().max().unwrap();
+                    lower + (upper - lower) / 2
+                }
+            }
+        };
+        UtxoStats {
+            count: self.count,
+            amount: self.amount,
+            min_amount: if self.count == 0 { 0 } else { self.min_amount },
+            max_amount: self.max_amount,
+            median_amount,
+            dust_count: self.dust_count,
+            oldest_block_daa_score: if self.oldest_block_daa_score == u64::MAX { 0 } else { self.oldest_block_daa_score },
+            newest_block_daa_score: self.newest_block_daa_score,
+        }
+    }
+}
+
+// splitmix64
+fn mix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
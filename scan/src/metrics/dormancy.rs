@@ -0,0 +1,135 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+pub enum Cutoff {
+    /// Coins that haven't moved for this many seconds
+    Age(u64),
+    /// Coins that haven't moved since the first seconds after genesis
+    GenesisWindow(u64),
+}
+
+/// Blocks per second of the network, which changes once at the activation DAA score
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockRate {
+    pub bps_before: u64,
+    pub bps_after: u64,
+    pub activation_daa_score: u64,
+}
+
+impl BlockRate {
+    pub fn constant(bps: u64) -> Self {
+        BlockRate { bps_before: bps, bps_after: bps, activation_daa_score: u64::MAX }
+    }
+
+    /// The DAA score `seconds` before `daa_score`
+    pub fn rewind(&self, daa_score: u64, seconds: u64) -> u64 {
+        if daa_score <= self.activation_daa_score {
+            return daa_score.saturating_sub(seconds.saturating_mul(self.bps_before));
+        }
+        let seconds_after = (daa_score - self.activation_daa_score) / self.bps_after;
+        if seconds <= seconds_after {
+            daa_score - seconds * self.bps_after
+        } else {
+            self.activation_daa_score.saturating_sub((seconds - seconds_after).saturating_mul(self.bps_before))
+        }
+    }
+
+    /// The DAA score `seconds` after `daa_score`
+    pub fn advance(&self, daa_score: u64, seconds: u64) -> u64 {
+        if daa_score >= self.activation_daa_score {
+            return daa_score.saturating_add(seconds.saturating_mul(self.bps_after));
+        }
+        let seconds_before = (self.activation_daa_score - daa_score) / self.bps_before;
+        if seconds <= seconds_before {
+            daa_score + seconds * self.bps_before
+        } else {
+            self.activation_daa_score.saturating_add((seconds - seconds_before).saturating_mul(self.bps_after))
+        }
+    }
+}
+
+pub struct Threshold {
+    pub name: String,
+    pub cutoff: Cutoff,
+    /// Coins created at or below this block DAA score are dormant, resolved when the scan starts
+    pub max_block_daa_score: u64,
+    pub amount: u64,
+    pub utxo_count: u64,
+    /// Max block DAA score and dormant amount of the previous snapshot
+    pub previous: Option<(u64, u64)>,
+    still_dormant_amount: u64,
+}
+
+impl Threshold {
+    pub fn new(name: String, cutoff: Cutoff, previous: Option<(u64, u64)>) -> Self {
+        Threshold { name, cutoff, max_block_daa_score: 0, amount: 0, utxo_count: 0, previous, still_dormant_amount: 0 }
+    }
+
+    /// Coins dormant in the previous snapshot which have been spent since
+    pub fn reawakened_amount(&self) -> Option<u64> {
+        self.previous.map(|(_, amount)| amount.saturating_sub(self.still_dormant_amount))
+    }
+}
+
+pub struct Dormancy<K> {
+    pub thresholds: Vec<Threshold>,
+    /// Dormant amount and oldest block DAA score per script, using the loosest threshold
+    pub scripts: HashMap<K, (u64, u64)>,
+    max_block_daa_score: Option<u64>,
+}
+
+impl<K: Hash + Eq + Clone> Dormancy<K> {
+    pub fn new(thresholds: Vec<Threshold>) -> Self {
+        Dormancy { thresholds, scripts: HashMap::new(), max_block_daa_score: None }
+    }
+
+    /// Resolves the threshold cutoffs, must be called before adding UTXOs
+    pub fn start(&mut self, virtual_daa_score: u64, genesis_daa_score: u64, block_rate: BlockRate) {
+        for threshold in self.thresholds.iter_mut() {
+            threshold.max_block_daa_score = match threshold.cutoff {
+                Cutoff::Age(seconds) => block_rate.rewind(virtual_daa_score, seconds),
+                Cutoff::GenesisWindow(seconds) => block_rate.advance(genesis_daa_score, seconds).min(virtual_daa_score),
+            };
+        }
+        self.max_block_daa_score = self.thresholds.iter().map(|t| t.max_block_daa_score).max();
+    }
+
+    pub fn add(&mut self, script: &K, amount: u64, block_daa_score: u64) {
+        let Some(max_block_daa_score) = self.max_block_daa_score else {
+            return;
+        };
+        for threshold in self.thresholds.iter_mut() {
+            if block_daa_score <= threshold.max_block_daa_score {
+                threshold.amount += amount;
+                threshold.utxo_count += 1;
+            }
+            if matches!(threshold.previous, Some((previous_max, _)) if block_daa_score <= previous_max) {
+                threshold.still_dormant_amount += amount;
+            }
+        }
+        if block_daa_score <= max_block_daa_score {
+            if let Some((a, oldest)) = self.scripts.get_mut(script) {
+                *a += amount;
+                *oldest = (*oldest).min(block_daa_score);
+            } else {
+                self.scripts.insert(script.clone(), (amount, block_daa_score));
+            }
+        }
+    }
+
+    pub fn script_count(&self, threshold: &Threshold) -> u64 {
+        self.scripts.values().filter(|(_, oldest)| *oldest <= threshold.max_block_daa_score).count() as u64
+    }
+
+    /// Returns the scripts with the largest dormant amount as (script, dormant amount, oldest block DAA score), largest first
+    pub fn largest(&self, count: usize) -> Vec<(&K, u64, u64)> {
+        let mut scripts: Vec<(&K, u64, u64)> = self.scripts.iter().map(|(k, &(amount, oldest))| (k, amount, oldest)).collect();
+        if scripts.len() > count {
+            scripts.select_nth_unstable_by_key(count, |s| Reverse(s.1));
+            scripts.truncate(count);
+        }
+        scripts.sort_unstable_by_key(|s| Reverse(s.1));
+        scripts
+    }
+}
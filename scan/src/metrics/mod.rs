@@ -0,0 +1,4 @@
+pub mod consolidation;
+pub mod dormancy;
+pub mod tiers;
+pub mod top;
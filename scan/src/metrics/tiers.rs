@@ -0,0 +1,27 @@
+use crate::tier_scheme::TierScheme;
+
+#[derive(Clone, Default)]
+pub struct Tier {
+    pub count: u64,
+    pub amount: u64,
+    pub utxo_count: u64,
+}
+
+pub struct Tiers {
+    pub scheme: TierScheme,
+    pub tiers: Vec<Tier>,
+}
+
+impl Tiers {
+    pub fn new(scheme: TierScheme) -> Self {
+        let tiers = vec![Tier::default(); scheme.tier_count()];
+        Tiers { scheme, tiers }
+    }
+
+    pub fn add(&mut self, amount: u64, utxo_count: u64) {
+        let tier = &mut self.tiers[self.scheme.tier(amount)];
+        tier.count += 1;
+        tier.amount += amount;
+        tier.utxo_count += utxo_count;
+    }
+}
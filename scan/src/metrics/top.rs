@@ -0,0 +1,47 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Keeps the `count` items with the largest amounts using a min-heap.
+/// Items are only built once they may qualify, ties are kept by the larger item so the result doesn't depend on the push order.
+pub struct TopN<T: Ord> {
+    count: usize,
+    heap: BinaryHeap<Reverse<(u64, T)>>,
+}
+
+impl<T: Ord> TopN<T> {
+    /// The heap is preallocated up to `max_capacity` items
+    pub fn new(count: usize, max_capacity: usize) -> Self {
+        TopN { count, heap: BinaryHeap::with_capacity(count.min(max_capacity)) }
+    }
+
+    pub fn push(&mut self, amount: u64, item: impl FnOnce() -> T) {
+        if self.heap.len() < self.count {
+            self.heap.push(Reverse((amount, item())));
+        } else if self.count > 0 {
+            let Reverse((min_amount, min_item)) = self.heap.peek().unwrap();
+            if amount > *min_amount {
+                self.heap.pop();
+                self.heap.push(Reverse((amount, item())));
+            } else if amount == *min_amount {
+                let item = item();
+                if item > *min_item {
+                    self.heap.pop();
+                    self.heap.push(Reverse((amount, item)));
+                }
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Largest first
+    pub fn into_sorted_vec(self) -> Vec<(u64, T)> {
+        self.heap.into_sorted_vec().into_iter().map(|Reverse(item)| item).collect()
+    }
+}
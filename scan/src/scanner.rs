@@ -0,0 +1,203 @@
+use crate::metrics::consolidation::ScriptUtxos;
+use crate::metrics::dormancy::{BlockRate, Dormancy, Threshold};
+use crate::metrics::tiers::Tiers;
+use crate::metrics::top::TopN;
+use crate::source::UtxoSource;
+use crate::tier_scheme::TierScheme;
+use humantime::format_duration;
+use kaspa_consensus_core::constants::SOMPI_PER_KASPA;
+use kaspa_consensus_core::tx::ScriptPublicKey;
+use log::{info, trace};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+// (amount, transaction id, index, block daa score, is coinbase, script version, script)
+pub type UtxoHeapEntry = (u64, [u8; 32], u32, u64, bool, u16, Vec<u8>);
+
+/// What to collect besides the per script balances.
+pub struct ScanOptions {
+    /// UTXOs below this amount (in sompi) only count towards the dust totals
+    pub ignore_dust_amounts: u64,
+    /// Schemes to bucket individual UTXOs by
+    pub utxo_tier_schemes: Vec<TierScheme>,
+    /// Number of largest UTXOs to keep (0 = disabled)
+    pub top_utxos_count: usize,
+    /// Scripts to total dust UTXOs for
+    pub watched_scripts: Vec<ScriptPublicKey>,
+    /// Per script amounts of UTXOs at least this many seconds old are kept (0 = disabled)
+    pub dormant_age: u64,
+    pub dormancy_thresholds: Vec<Threshold>,
+    pub genesis_daa_score: u64,
+    /// Converts the dormancy ages to DAA scores
+    pub block_rate: BlockRate,
+    /// Scripts with at least this many UTXOs (excluding dust) get consolidation stats
+    pub utxo_count_threshold: u64,
+}
+
+pub struct ScanResult {
+    pub utxo_count: u64,
+    pub total_amount: u64,
+    pub dust_count: u64,
+    pub dust_amount: u64,
+    /// (amount, utxo count) per script, excluding dust
+    pub script_amounts: HashMap<ScriptPublicKey, (u64, u64)>,
+    pub utxo_tiers: Vec<Tiers>,
+    /// Largest first
+    pub top_utxos: Vec<UtxoHeapEntry>,
+    /// Dust (amount, utxo count) per watched script
+    pub watched_dust: HashMap<ScriptPublicKey, (u64, u64)>,
+    pub dormant_amounts: HashMap<ScriptPublicKey, u64>,
+    pub dormancy: Dormancy<ScriptPublicKey>,
+    /// Consolidation stats of the scripts reaching the UTXO count threshold
+    pub script_utxos: HashMap<ScriptPublicKey, ScriptUtxos>,
+}
+
+/// Returned when the scan is stopped through the run flag.
+#[derive(Debug)]
+pub struct ScanCancelled;
+
+impl fmt::Display for ScanCancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Scan cancelled")
+    }
+}
+
+impl Error for ScanCancelled {}
+
+pub fn scan(source: &dyn UtxoSource, options: ScanOptions, run: &AtomicBool) -> Result<ScanResult, Box<dyn Error>> {
+    let ScanOptions {
+        ignore_dust_amounts,
+        utxo_tier_schemes,
+        top_utxos_count,
+        watched_scripts,
+        dormant_age,
+        dormancy_thresholds,
+        genesis_daa_score,
+        block_rate,
+        utxo_count_threshold,
+    } = options;
+    let daa_score = source.anchor().daa_score;
+    let mut utxo_tiers: Vec<Tiers> = utxo_tier_schemes.into_iter().map(Tiers::new).collect();
+    let mut top_utxos = TopN::new(top_utxos_count, top_utxos_count);
+    let mut watched_dust: HashMap<ScriptPublicKey, (u64, u64)> = watched_scripts.into_iter().map(|spk| (spk, (0, 0))).collect();
+    let mut dormant_amounts = HashMap::new();
+    let mut dormancy = Dormancy::new(dormancy_thresholds);
+    dormancy.start(daa_score, genesis_daa_score, block_rate);
+    let dormant_max_block_daa_score = block_rate.rewind(daa_score, dormant_age);
+
+    let mut count = 0u64;
+    let mut total_amount = 0u64;
+    let mut dust_count = 0u64;
+    let mut dust_total_amount = 0u64;
+    let mut script_amounts: HashMap<ScriptPublicKey, (u64, u64)> = HashMap::new();
+
+    let start_time = Instant::now();
+    for result in source.utxos()? {
+        let (outpoint, entry) = result?;
+        let amount = entry.amount;
+        count += 1;
+        total_amount += amount;
+        if amount < ignore_dust_amounts {
+            trace!("Ignoring dust UTXO of {amount} sompi");
+            dust_count += 1;
+            dust_total_amount += amount;
+            if let Some((a, c)) = watched_dust.get_mut(&entry.script_public_key) {
+                *a += amount;
+                *c += 1;
+            }
+        } else {
+            if let Some((a, c)) = script_amounts.get_mut(&entry.script_public_key) {
+                *a += amount;
+                *c += 1;
+            } else {
+                script_amounts.insert(entry.script_public_key.clone(), (amount, 1));
+            }
+            utxo_tiers.iter_mut().for_each(|t| t.add(amount, 1));
+            if dormant_age > 0 && entry.block_daa_score <= dormant_max_block_daa_score {
+                *dormant_amounts.entry(entry.script_public_key.clone()).or_insert(0) += amount;
+            }
+            dormancy.add(&entry.script_public_key, amount, entry.block_daa_score);
+            top_utxos.push(amount, || {
+                (
+                    outpoint.transaction_id.as_bytes(),
+                    outpoint.index,
+                    entry.block_daa_score,
+                    entry.is_coinbase,
+                    entry.script_public_key.version(),
+                    entry.script_public_key.script().to_vec(),
+                )
+            });
+        }
+        if count.is_multiple_of(1_000_000) {
+            info!(
+                "Processed {count} UTXOs, total amount {} KAS (dust: {dust_count}/{}, scripts: {})",
+                total_amount / SOMPI_PER_KASPA,
+                dust_total_amount / SOMPI_PER_KASPA,
+                script_amounts.len()
+            );
+        }
+        if !run.load(Ordering::Relaxed) {
+            return Err(ScanCancelled.into());
+        }
+    }
+    info!(
+        "Done processing {count} UTXOs, total amount {} (dust: {dust_count}/{}, scripts: {}), time used: {}",
+        total_amount / SOMPI_PER_KASPA,
+        dust_total_amount / SOMPI_PER_KASPA,
+        script_amounts.len(),
+        format_duration(Duration::from_secs(start_time.elapsed().as_secs()))
+    );
+
+    let script_utxos = consolidation_stats(source, &script_amounts, ignore_dust_amounts, utxo_count_threshold, run)?;
+    Ok(ScanResult {
+        utxo_count: count,
+        total_amount,
+        dust_count,
+        dust_amount: dust_total_amount,
+        script_amounts,
+        utxo_tiers,
+        top_utxos: top_utxos.into_sorted_vec().into_iter().map(|(amount, (t, i, d, c, v, s))| (amount, t, i, d, c, v, s)).collect(),
+        watched_dust,
+        dormant_amounts,
+        dormancy,
+        script_utxos,
+    })
+}
+
+/// Second pass over the set, only for the scripts reaching the UTXO count threshold, so the first pass keeps no more than
+/// (amount, utxo count) per script. On a live datadir the stats reflect the set at the time of the second pass.
+fn consolidation_stats(
+    source: &dyn UtxoSource,
+    script_amounts: &HashMap<ScriptPublicKey, (u64, u64)>,
+    ignore_dust_amounts: u64,
+    utxo_count_threshold: u64,
+    run: &AtomicBool,
+) -> Result<HashMap<ScriptPublicKey, ScriptUtxos>, Box<dyn Error>> {
+    let mut script_utxos: HashMap<ScriptPublicKey, ScriptUtxos> = script_amounts
+        .iter()
+        .filter(|(_, &(_, count))| count >= utxo_count_threshold)
+        .map(|(script, _)| (script.clone(), ScriptUtxos::default()))
+        .collect();
+    if script_utxos.is_empty() {
+        return Ok(script_utxos);
+    }
+    let start_time = Instant::now();
+    for result in source.utxos()? {
+        let (_, entry) = result?;
+        if let Some(utxos) = script_utxos.get_mut(&entry.script_public_key) {
+            utxos.add(entry.amount, entry.block_daa_score, entry.amount < ignore_dust_amounts);
+        }
+        if !run.load(Ordering::Relaxed) {
+            return Err(ScanCancelled.into());
+        }
+    }
+    info!(
+        "Collected consolidation stats of {} scripts with at least {utxo_count_threshold} UTXOs, time used: {}",
+        script_utxos.len(),
+        format_duration(Duration::from_secs(start_time.elapsed().as_secs()))
+    );
+    Ok(script_utxos)
+}
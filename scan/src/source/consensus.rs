@@ -0,0 +1,85 @@
+use crate::source::{SourceAnchor, UtxoIterator, UtxoSource};
+use kaspa_consensus::consensus::storage::ConsensusStorage;
+use kaspa_consensus::model::stores::headers::HeaderStoreReader;
+use kaspa_consensus::model::stores::pruning_utxoset::PruningUtxosetStores;
+use kaspa_consensus::model::stores::virtual_state::{VirtualStateStoreReader, VirtualStores};
+use kaspa_consensus_core::config::ConfigBuilder;
+use kaspa_consensus_core::network::NetworkId;
+use kaspa_database::prelude::DB;
+use parking_lot::RwLockReadGuard;
+use rocksdb::{DBWithThreadMode, MultiThreaded};
+use std::error::Error;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Opens a consensus database read-only, either kaspad's live consensus directory or a RocksDB checkpoint of it
+pub fn open_storage(network_id: NetworkId, db_path: &Path) -> Result<Arc<ConsensusStorage>, Box<dyn Error>> {
+    let mut opts = rocksdb::Options::default();
+    opts.create_if_missing(false);
+    opts.set_max_open_files(128);
+    opts.set_allow_mmap_reads(true);
+    let guard = kaspa_utils::fd_budget::acquire_guard(128).unwrap();
+
+    let db = Arc::new(DB::new(<DBWithThreadMode<MultiThreaded>>::open_for_read_only(&opts, db_path.to_str().unwrap(), false)?, guard));
+    let config = Arc::new(ConfigBuilder::new(network_id.into()).adjust_perf_params_to_consensus_params().build());
+    Ok(ConsensusStorage::new(db, config))
+}
+
+/// Which of the UTXO sets kaspad stores to read
+#[derive(Clone, Copy)]
+pub enum UtxoSetKind {
+    Virtual,
+    PruningPoint,
+}
+
+enum UtxoSetGuard<'a> {
+    Virtual(RwLockReadGuard<'a, VirtualStores>),
+    PruningPoint(RwLockReadGuard<'a, PruningUtxosetStores>),
+}
+
+/// A UTXO set in a consensus database, the store is read-locked for the lifetime of the source
+pub struct ConsensusSource<'a> {
+    utxo_set: UtxoSetGuard<'a>,
+    anchor: SourceAnchor,
+}
+
+impl<'a> ConsensusSource<'a> {
+    pub fn new(storage: &'a ConsensusStorage, kind: UtxoSetKind) -> Result<Self, Box<dyn Error>> {
+        match kind {
+            UtxoSetKind::Virtual => {
+                let virtual_stores = storage.virtual_stores.read();
+                let virtual_state = virtual_stores.state.get()?;
+                let anchor = SourceAnchor {
+                    mode: "virtual".to_string(),
+                    block_hash: virtual_state.ghostdag_data.selected_parent.as_bytes(),
+                    daa_score: virtual_state.daa_score,
+                };
+                Ok(ConsensusSource { utxo_set: UtxoSetGuard::Virtual(virtual_stores), anchor })
+            }
+            UtxoSetKind::PruningPoint => {
+                let pruning_utxoset_stores = storage.pruning_utxoset_stores.read();
+                let utxoset_position = pruning_utxoset_stores.utxoset_position()?;
+                let anchor = SourceAnchor {
+                    mode: "pruning_point".to_string(),
+                    block_hash: utxoset_position.as_bytes(),
+                    daa_score: storage.headers_store.get_daa_score(utxoset_position)?,
+                };
+                Ok(ConsensusSource { utxo_set: UtxoSetGuard::PruningPoint(pruning_utxoset_stores), anchor })
+            }
+        }
+    }
+}
+
+impl UtxoSource for ConsensusSource<'_> {
+    fn anchor(&self) -> &SourceAnchor {
+        &self.anchor
+    }
+
+    fn utxos(&self) -> Result<UtxoIterator<'_>, Box<dyn Error>> {
+        let utxo_set = match &self.utxo_set {
+            UtxoSetGuard::Virtual(virtual_stores) => &virtual_stores.utxo_set,
+            UtxoSetGuard::PruningPoint(pruning_utxoset_stores) => &pruning_utxoset_stores.utxo_set,
+        };
+        Ok(Box::new(utxo_set.iterator()))
+    }
+}
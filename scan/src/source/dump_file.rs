@@ -0,0 +1,61 @@
+use crate::dump::format::{DumpHeader, DumpReader, UtxoRecord};
+use crate::source::{SourceAnchor, UtxoIterator, UtxoSource};
+use kaspa_consensus_core::tx::{ScriptPublicKey, TransactionId, TransactionOutpoint, UtxoEntry};
+use std::error::Error;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A UTXO set in a file written by `DumpWriter`, the file is reopened for every pass
+pub struct DumpFileSource {
+    path: PathBuf,
+    header: DumpHeader,
+    anchor: SourceAnchor,
+}
+
+impl DumpFileSource {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let header = DumpReader::open(&path)?.header().clone();
+        let anchor = SourceAnchor { mode: header.anchor_mode.clone(), block_hash: header.anchor_hash, daa_score: header.daa_score };
+        Ok(DumpFileSource { path: path.as_ref().to_path_buf(), header, anchor })
+    }
+
+    pub fn header(&self) -> &DumpHeader {
+        &self.header
+    }
+}
+
+impl UtxoSource for DumpFileSource {
+    fn anchor(&self) -> &SourceAnchor {
+        &self.anchor
+    }
+
+    fn utxos(&self) -> Result<UtxoIterator<'_>, Box<dyn Error>> {
+        let reader = DumpReader::open(&self.path)?;
+        Ok(Box::new(reader.map(|r| -> Result<_, Box<dyn Error>> { Ok(utxo_from_record(r?)) })))
+    }
+}
+
+pub fn utxo_from_record(record: UtxoRecord) -> (TransactionOutpoint, Arc<UtxoEntry>) {
+    (
+        TransactionOutpoint::new(TransactionId::from_bytes(record.transaction_id), record.index),
+        Arc::new(UtxoEntry::new(
+            record.amount,
+            ScriptPublicKey::from_vec(record.script_version, record.script),
+            record.block_daa_score,
+            record.is_coinbase,
+        )),
+    )
+}
+
+pub fn record_from_utxo(outpoint: &TransactionOutpoint, entry: &UtxoEntry) -> UtxoRecord {
+    UtxoRecord {
+        transaction_id: outpoint.transaction_id.as_bytes(),
+        index: outpoint.index,
+        amount: entry.amount,
+        block_daa_score: entry.block_daa_score,
+        is_coinbase: entry.is_coinbase,
+        script_version: entry.script_public_key.version(),
+        script: entry.script_public_key.script().to_vec(),
+    }
+}
@@ -0,0 +1,26 @@
+use crate::source::{SourceAnchor, UtxoIterator, UtxoSource};
+use kaspa_consensus_core::tx::{TransactionOutpoint, UtxoEntry};
+use std::error::Error;
+use std::sync::Arc;
+
+/// A UTXO set held in memory, e.g. a synthetic set for tests
+pub struct MemorySource {
+    anchor: SourceAnchor,
+    utxos: Vec<(TransactionOutpoint, Arc<UtxoEntry>)>,
+}
+
+impl MemorySource {
+    pub fn new(anchor: SourceAnchor, utxos: Vec<(TransactionOutpoint, Arc<UtxoEntry>)>) -> Self {
+        MemorySource { anchor, utxos }
+    }
+}
+
+impl UtxoSource for MemorySource {
+    fn anchor(&self) -> &SourceAnchor {
+        &self.anchor
+    }
+
+    fn utxos(&self) -> Result<UtxoIterator<'_>, Box<dyn Error>> {
+        Ok(Box::new(self.utxos.iter().cloned().map(Ok)))
+    }
+}
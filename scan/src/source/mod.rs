@@ -0,0 +1,26 @@
+pub mod consensus;
+pub mod dump_file;
+pub mod memory;
+
+use kaspa_consensus_core::tx::{TransactionOutpoint, UtxoEntry};
+use std::error::Error;
+use std::sync::Arc;
+
+pub type UtxoIterator<'a> = Box<dyn Iterator<Item = Result<(TransactionOutpoint, Arc<UtxoEntry>), Box<dyn Error>>> + 'a>;
+
+/// The chain position a UTXO set corresponds to
+#[derive(Clone, Debug)]
+pub struct SourceAnchor {
+    /// How the anchor was picked, e.g. "virtual" or "pruning_point"
+    pub mode: String,
+    pub block_hash: [u8; 32],
+    pub daa_score: u64,
+}
+
+/// A UTXO set the scanner can iterate, once per scan pass.
+pub trait UtxoSource {
+    fn anchor(&self) -> &SourceAnchor;
+
+    /// Iterates the UTXO set from the start
+    fn utxos(&self) -> Result<UtxoIterator<'_>, Box<dyn Error>>;
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A named set of ascending tier boundaries (in sompi).
+/// Tier 0 holds balances below the first boundary, the last tier is unbounded.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TierScheme {
+    pub name: String,
+    pub boundaries: Vec<u64>,
+}
+
+impl TierScheme {
+    pub fn tier_count(&self) -> usize {
+        self.boundaries.len() + 1
+    }
+
+    pub fn tier(&self, amount: u64) -> usize {
+        self.boundaries.partition_point(|&b| b <= amount)
+    }
+
+    pub fn bounds(&self, tier: usize) -> (u64, Option<u64>) {
+        let lower = if tier == 0 { 0 } else { self.boundaries[tier - 1] };
+        (lower, self.boundaries.get(tier).copied())
+    }
+}
+
+impl FromStr for TierScheme {
+    type Err = String;
+
+    /// Parses either `<name>=log:<base>:<start>` or `<name>=<boundary>,<boundary>,...`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, definition) = s.split_once('=').ok_or(format!("Expected <name>=<definition>, got '{s}'"))?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(format!("Missing tier scheme name in '{s}'"));
+        }
+        let boundaries = if let Some(params) = definition.strip_prefix("log:") {
+            let (base, start) = params.split_once(':').ok_or(format!("Expected log:<base>:<start>, got '{definition}'"))?;
+            let base = base.trim().parse::<f64>().map_err(|e| format!("Invalid log base '{base}': {e}"))?;
+            let start = start.trim().parse::<u64>().map_err(|e| format!("Invalid log start '{start}': {e}"))?;
+            if base <= 1.0 || start == 0 {
+                return Err(format!("Log base must be > 1 and start > 0, got '{definition}'"));
+            }
+            let mut boundaries = vec![];
+            let mut boundary = start as f64;
+            while boundary <= i64::MAX as f64 {
+                let b = boundary.round() as u64;
+                if boundaries.last() != Some(&b) {
+                    boundaries.push(b);
+                }
+                boundary *= base;
+            }
+            boundaries
+        } else {
+            definition
+                .split(',')
+                .map(|b| b.trim().parse::<u64>().map_err(|e| format!("Invalid tier boundary '{b}': {e}")))
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        if boundaries.is_empty() || boundaries.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(format!("Tier boundaries must be strictly ascending, got '{definition}'"));
+        }
+        if boundaries.len() >= i16::MAX as usize {
+            return Err(format!("Too many tiers in '{definition}'"));
+        }
+        Ok(TierScheme { name: name.to_string(), boundaries })
+    }
+}
+
+impl fmt::Display for TierScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({} tiers)", self.name, self.tier_count())
+    }
+}
@@ -0,0 +1,439 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use kaspa_consensus_core::tx::ScriptPublicKey;
+use simply_kaspa_utxo_exporter_scan::aggregate::{aggregate, Aggregation, AggregationOptions, ScriptClass, TopEntity};
+use simply_kaspa_utxo_exporter_scan::dump::format::{DumpHeader, DumpReader, DumpWriter, UtxoRecord, MAX_SCRIPT_LEN};
+use simply_kaspa_utxo_exporter_scan::fixture::{non_standard_script, p2pk_ecdsa_script, p2pk_script, p2sh_script, Fixture};
+use simply_kaspa_utxo_exporter_scan::metrics::consolidation::{ScriptUtxos, MEDIAN_SAMPLE_SIZE};
+use simply_kaspa_utxo_exporter_scan::metrics::dormancy::{BlockRate, Cutoff, Threshold};
+use simply_kaspa_utxo_exporter_scan::scanner::{scan, ScanCancelled, ScanOptions, ScanResult};
+use simply_kaspa_utxo_exporter_scan::source::dump_file::DumpFileSource;
+use simply_kaspa_utxo_exporter_scan::source::UtxoSource;
+use simply_kaspa_utxo_exporter_scan::tier_scheme::TierScheme;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::atomic::AtomicBool;
+
+const KAS: u64 = 100_000_000;
+const DUST: u64 = 1_000;
+
+fn default_scheme() -> TierScheme {
+    TierScheme::from_str("default=log:10:100000000").unwrap()
+}
+
+fn options(watched_scripts: Vec<ScriptPublicKey>) -> ScanOptions {
+    ScanOptions {
+        ignore_dust_amounts: DUST,
+        utxo_tier_schemes: vec![default_scheme()],
+        top_utxos_count: 3,
+        watched_scripts,
+        dormant_age: 5_000,
+        dormancy_thresholds: vec![
+            Threshold::new("age".to_string(), Cutoff::Age(5_000), None),
+            Threshold::new("genesis".to_string(), Cutoff::GenesisWindow(100), None),
+        ],
+        genesis_daa_score: 0,
+        block_rate: BlockRate::constant(1),
+        utxo_count_threshold: 2,
+    }
+}
+
+/// A: two UTXOs around the 1 KAS boundary, B: 20B KAS in two UTXOs, C: one dust and one boundary UTXO,
+/// D: a non-standard script, E: a zero amount UTXO, F: six equal UTXOs
+fn fixture() -> Fixture {
+    let mut fixture = Fixture::new(10_000);
+    fixture
+        .add(&p2pk_script(1), KAS, 100)
+        .add(&p2pk_script(1), KAS - 1, 200)
+        .add_coinbase(&p2pk_ecdsa_script(2), 10_000_000_000 * KAS, 50)
+        .add(&p2pk_ecdsa_script(2), 10_000_000_000 * KAS, 9_000)
+        .add(&p2sh_script(3), DUST - 1, 9_999)
+        .add(&p2sh_script(3), DUST, 9_999)
+        .add(&non_standard_script(4), 50 * KAS, 1)
+        .add(&p2pk_script(5), 0, 9_000)
+        .add_many(&p2pk_script(6), 10 * KAS, 6, 5_000);
+    fixture
+}
+
+fn run(source: &dyn UtxoSource, options: ScanOptions) -> ScanResult {
+    scan(source, options, &AtomicBool::new(true)).unwrap()
+}
+
+fn tier_counts(tiers: &simply_kaspa_utxo_exporter_scan::metrics::tiers::Tiers) -> Vec<(usize, u64, u64, u64)> {
+    tiers.tiers.iter().enumerate().filter(|(_, t)| t.count > 0).map(|(i, t)| (i, t.count, t.amount, t.utxo_count)).collect()
+}
+
+#[test]
+fn totals_and_dust() {
+    let result = run(&fixture().source(), options(vec![p2sh_script(3), p2pk_script(5), p2pk_script(7)]));
+    assert_eq!(result.utxo_count, 14);
+    assert_eq!(result.total_amount, 20_000_000_000 * KAS + 2 * KAS - 1 + (DUST - 1) + DUST + 110 * KAS);
+    assert_eq!(result.dust_count, 2);
+    assert_eq!(result.dust_amount, DUST - 1);
+    assert_eq!(result.watched_dust[&p2sh_script(3)], (DUST - 1, 1));
+    assert_eq!(result.watched_dust[&p2pk_script(5)], (0, 1));
+    assert_eq!(result.watched_dust[&p2pk_script(7)], (0, 0));
+}
+
+#[test]
+fn script_amounts_exclude_dust() {
+    let result = run(&fixture().source(), options(vec![]));
+    assert_eq!(result.script_amounts.len(), 5);
+    assert_eq!(result.script_amounts[&p2pk_script(1)], (2 * KAS - 1, 2));
+    assert_eq!(result.script_amounts[&p2pk_ecdsa_script(2)], (20_000_000_000 * KAS, 2));
+    assert_eq!(result.script_amounts[&p2sh_script(3)], (DUST, 1));
+    assert_eq!(result.script_amounts[&non_standard_script(4)], (50 * KAS, 1));
+    assert_eq!(result.script_amounts[&p2pk_script(6)], (60 * KAS, 6));
+    assert!(!result.script_amounts.contains_key(&p2pk_script(5)));
+}
+
+#[test]
+fn utxo_tiers() {
+    let result = run(&fixture().source(), options(vec![]));
+    assert_eq!(result.utxo_tiers.len(), 1);
+    let tiers = &result.utxo_tiers[0];
+    assert_eq!(tiers.tiers.len(), 12);
+    // (tier, count, amount, utxo count), boundaries belong to the tier above
+    assert_eq!(
+        tier_counts(tiers),
+        vec![(0, 2, KAS - 1 + DUST, 2), (1, 1, KAS, 1), (2, 7, 110 * KAS, 7), (11, 2, 20_000_000_000 * KAS, 2)]
+    );
+}
+
+fn aggregation_options(top_scripts_count: usize) -> AggregationOptions {
+    AggregationOptions {
+        tier_schemes: vec![default_scheme()],
+        top_scripts_count,
+        top_scripts_min_amount: 0,
+        exclusion_count: 0,
+        entities: false,
+    }
+}
+
+fn aggregated<'a>(
+    result: &ScanResult,
+    options: &AggregationOptions,
+    classify: impl Fn(&ScriptPublicKey) -> ScriptClass<'a>,
+) -> Aggregation {
+    aggregate(&result.script_amounts, options, classify, &AtomicBool::new(true)).unwrap()
+}
+
+fn top_scripts(aggregation: &Aggregation) -> Vec<(ScriptPublicKey, u64)> {
+    aggregation.top_scripts.iter().map(|(amount, script)| (ScriptPublicKey::from_vec(0, script.clone()), *amount)).collect()
+}
+
+#[test]
+fn script_tiers() {
+    let result = run(&fixture().source(), options(vec![]));
+    let mut options = aggregation_options(0);
+    options.tier_schemes.push(TierScheme::from_str("coarse=100000000000").unwrap());
+    let tiers = aggregated(&result, &options, |_| ScriptClass::default()).tiers;
+    assert_eq!(
+        tier_counts(&tiers[0]),
+        vec![(0, 1, DUST, 1), (1, 1, 2 * KAS - 1, 2), (2, 2, 110 * KAS, 7), (11, 1, 20_000_000_000 * KAS, 2)]
+    );
+    assert_eq!(tier_counts(&tiers[1]), vec![(0, 4, 112 * KAS - 1 + DUST, 10), (1, 1, 20_000_000_000 * KAS, 2)]);
+}
+
+#[test]
+fn top_scripts_ranking() {
+    let result = run(&fixture().source(), options(vec![]));
+    let aggregation = aggregated(&result, &aggregation_options(3), |_| ScriptClass::default());
+    assert_eq!(
+        top_scripts(&aggregation),
+        vec![(p2pk_ecdsa_script(2), 20_000_000_000 * KAS), (p2pk_script(6), 60 * KAS), (non_standard_script(4), 50 * KAS)]
+    );
+    assert_eq!(aggregated(&result, &aggregation_options(100), |_| ScriptClass::default()).top_scripts.len(), 5);
+    // 0 ranks all scripts
+    assert_eq!(aggregated(&result, &aggregation_options(0), |_| ScriptClass::default()).top_scripts.len(), 5);
+}
+
+#[test]
+fn min_amount_and_exclusions() {
+    let result = run(&fixture().source(), options(vec![]));
+    let options = AggregationOptions { top_scripts_min_amount: 50 * KAS, exclusion_count: 2, ..aggregation_options(0) };
+    let aggregation = aggregated(&result, &options, |script| ScriptClass {
+        exclusion: (*script == p2pk_ecdsa_script(2)).then_some(1),
+        ..Default::default()
+    });
+    // Excluded scripts are still ranked, scripts below the minimum amount aren't
+    assert_eq!(
+        top_scripts(&aggregation),
+        vec![(p2pk_ecdsa_script(2), 20_000_000_000 * KAS), (p2pk_script(6), 60 * KAS), (non_standard_script(4), 50 * KAS)]
+    );
+    let excluded: Vec<_> = aggregation.excluded.iter().map(|e| (e.script_count, e.utxo_count, e.amount)).collect();
+    assert_eq!(excluded, vec![(0, 0, 0), (1, 2, 20_000_000_000 * KAS)]);
+    // Neither the excluded script nor the dust UTXOs are in the balances and tiers
+    assert_eq!(aggregation.balances, vec![DUST, 2 * KAS - 1, 50 * KAS, 60 * KAS]);
+    assert_eq!(tier_counts(&aggregation.tiers[0]), vec![(0, 1, DUST, 1), (1, 1, 2 * KAS - 1, 2), (2, 2, 110 * KAS, 7)]);
+    assert!(aggregation.entity_tiers.is_empty() && aggregation.top_entities.is_empty());
+}
+
+#[test]
+fn ties_rank_the_larger_script() {
+    let mut fixture = Fixture::new(100);
+    (1..=6).for_each(|seed| {
+        fixture.add(&p2pk_script(seed), 5 * KAS, 10);
+    });
+    fixture.add(&p2pk_script(7), 6 * KAS, 10);
+    let expected = vec![(p2pk_script(7), 6 * KAS), (p2pk_script(6), 5 * KAS), (p2pk_script(5), 5 * KAS)];
+    // The map order differs between scans, the ranking doesn't
+    for _ in 0..10 {
+        let result = run(&fixture.source(), options(vec![]));
+        assert_eq!(top_scripts(&aggregated(&result, &aggregation_options(3), |_| ScriptClass::default())), expected);
+    }
+}
+
+#[test]
+fn entities() {
+    let result = run(&fixture().source(), options(vec![]));
+    let entity_options = AggregationOptions { exclusion_count: 1, entities: true, ..aggregation_options(0) };
+    let aggregation = aggregated(&result, &entity_options, |script| {
+        // Only one of the exchange's scripts has a category, it applies to the entity whatever the map order
+        if *script == p2pk_script(1) {
+            ScriptClass { exclusion: None, entity: Some(("exchange", None)) }
+        } else if *script == p2pk_script(6) {
+            ScriptClass { exclusion: None, entity: Some(("exchange", Some("cex"))) }
+        } else if *script == non_standard_script(4) {
+            ScriptClass { exclusion: Some(0), entity: Some(("miner", None)) }
+        } else {
+            ScriptClass::default()
+        }
+    });
+    let top_entities: Vec<_> = aggregation
+        .top_entities
+        .iter()
+        .map(|(amount, entity)| match entity {
+            TopEntity::Entity(name, balance) => {
+                (*amount, name.clone(), balance.category.clone(), balance.script_count, balance.utxo_count, balance.excluded)
+            }
+            TopEntity::Script(script) => (*amount, format!("{script:02x?}"), None, 1, 0, false),
+        })
+        .collect();
+    assert_eq!(
+        top_entities,
+        vec![
+            (20_000_000_000 * KAS, format!("{:02x?}", p2pk_ecdsa_script(2).script()), None, 1, 0, false),
+            (62 * KAS - 1, "exchange".to_string(), Some("cex".to_string()), 2, 8, false),
+            (50 * KAS, "miner".to_string(), None, 1, 1, true),
+            (DUST, format!("{:02x?}", p2sh_script(3).script()), None, 1, 0, false),
+        ]
+    );
+    // The excluded entity is left out of the entity tiers
+    assert_eq!(
+        tier_counts(&aggregation.entity_tiers[0]),
+        vec![(0, 1, DUST, 1), (2, 1, 62 * KAS - 1, 8), (11, 1, 20_000_000_000 * KAS, 2)]
+    );
+
+    // Entities rank before unlabeled scripts of the same amount, even a larger script
+    let mut fixture = Fixture::new(100);
+    fixture.add(&p2pk_script(1), KAS, 10).add(&p2pk_script(2), KAS, 10);
+    let tied = run(&fixture.source(), options(vec![]));
+    let top_entities = aggregated(&tied, &AggregationOptions { entities: true, ..aggregation_options(1) }, |script| ScriptClass {
+        exclusion: None,
+        entity: (*script == p2pk_script(1)).then_some(("a", None)),
+    })
+    .top_entities;
+    assert!(matches!(top_entities.as_slice(), [(KAS, TopEntity::Entity(name, _))] if name == "a"));
+}
+
+#[test]
+fn top_utxos_ranking() {
+    let result = run(&fixture().source(), options(vec![]));
+    assert_eq!(result.top_utxos.iter().map(|u| u.0).collect::<Vec<_>>(), vec![10_000_000_000 * KAS, 10_000_000_000 * KAS, 50 * KAS]);
+    assert_eq!(result.top_utxos.iter().filter(|u| u.4).count(), 1);
+    assert_eq!(result.top_utxos[2].6, non_standard_script(4).script().to_vec());
+
+    let mut options = options(vec![]);
+    options.top_utxos_count = 0;
+    assert!(run(&fixture().source(), options).top_utxos.is_empty());
+}
+
+#[test]
+fn dormancy_thresholds_are_inclusive() {
+    let result = run(&fixture().source(), options(vec![]));
+    // Created at or before DAA score 10_000 - 5_000
+    assert_eq!(result.dormant_amounts.len(), 4);
+    assert_eq!(result.dormant_amounts[&p2pk_script(1)], 2 * KAS - 1);
+    assert_eq!(result.dormant_amounts[&p2pk_ecdsa_script(2)], 10_000_000_000 * KAS);
+    assert_eq!(result.dormant_amounts[&non_standard_script(4)], 50 * KAS);
+    assert_eq!(result.dormant_amounts[&p2pk_script(6)], 60 * KAS);
+
+    let age = &result.dormancy.thresholds[0];
+    assert_eq!(age.max_block_daa_score, 5_000);
+    assert_eq!((age.amount, age.utxo_count), (10_000_000_000 * KAS + 112 * KAS - 1, 10));
+    assert_eq!(result.dormancy.script_count(age), 4);
+
+    let genesis = &result.dormancy.thresholds[1];
+    assert_eq!(genesis.max_block_daa_score, 100);
+    assert_eq!((genesis.amount, genesis.utxo_count), (10_000_000_000 * KAS + 51 * KAS, 3));
+    assert_eq!(result.dormancy.script_count(genesis), 3);
+}
+
+#[test]
+fn dormancy_thresholds_around_the_bps_activation() {
+    // 1 BPS until DAA score 6_000, 10 BPS after, the fixture virtual DAA score is 10_000
+    let block_rate = BlockRate { bps_before: 1, bps_after: 10, activation_daa_score: 6_000 };
+    let mut options = options(vec![]);
+    options.block_rate = block_rate;
+    options.dormant_age = 500;
+    options.dormancy_thresholds = vec![
+        Threshold::new("after".to_string(), Cutoff::Age(100), None),
+        Threshold::new("before".to_string(), Cutoff::Age(500), None),
+        Threshold::new("genesis".to_string(), Cutoff::GenesisWindow(6_100), None),
+    ];
+    let result = run(&fixture().source(), options);
+    let cutoffs: Vec<u64> = result.dormancy.thresholds.iter().map(|t| t.max_block_daa_score).collect();
+    // 400 seconds after activation, 100 seconds before
+    assert_eq!(cutoffs, [9_000, 5_900, 7_000]);
+    assert_eq!(result.dormant_amounts.len(), 4);
+    assert_eq!(result.dormant_amounts[&p2pk_ecdsa_script(2)], 10_000_000_000 * KAS);
+
+    assert_eq!(block_rate.rewind(10_000, 400), 6_000);
+    assert_eq!(block_rate.rewind(5_000, 100), 4_900);
+    assert_eq!(block_rate.rewind(10_000, 1_000_000), 0);
+    assert_eq!(block_rate.advance(5_000, 1_000), 6_000);
+    assert_eq!(block_rate.advance(7_000, 10), 7_100);
+    assert_eq!(block_rate.advance(0, u64::MAX), u64::MAX);
+}
+
+#[test]
+fn consolidation_threshold() {
+    let result = run(&fixture().source(), options(vec![]));
+    let mut scripts: Vec<_> = result.script_utxos.into_iter().collect();
+    scripts.sort_by_key(|(s, _)| s.script().to_vec());
+    assert_eq!(scripts.iter().map(|(s, _)| s.clone()).collect::<Vec<_>>(), vec![p2pk_script(1), p2pk_script(6), p2pk_ecdsa_script(2)]);
+
+    let stats: Vec<_> = scripts.into_iter().map(|(_, utxos)| utxos.stats()).collect();
+    assert_eq!((stats[0].min_amount, stats[0].max_amount, stats[0].median_amount), (KAS - 1, KAS, KAS - 1));
+    assert_eq!((stats[0].oldest_block_daa_score, stats[0].newest_block_daa_score), (100, 200));
+    assert_eq!((stats[1].amount, stats[1].median_amount, stats[1].dust_count), (60 * KAS, 10 * KAS, 0));
+    assert_eq!((stats[2].oldest_block_daa_score, stats[2].newest_block_daa_score), (50, 9_000));
+
+    // Dust doesn't count towards the threshold, but is counted once a script qualifies
+    let mut options = options(vec![]);
+    options.utxo_count_threshold = 1;
+    let result = run(&fixture().source(), options);
+    assert_eq!(result.script_utxos.len(), 5);
+    let stats = result.script_utxos.into_iter().find(|(s, _)| *s == p2sh_script(3)).unwrap().1.stats();
+    assert_eq!((stats.amount, stats.dust_count, stats.oldest_block_daa_score), (DUST, 1, 9_999));
+}
+
+#[test]
+fn median_of_large_scripts() {
+    // Exact while all amounts fit the sample
+    let mut utxos = ScriptUtxos::default();
+    [7, 3, 9, 1].into_iter().for_each(|amount| utxos.add(amount * KAS, 10, false));
+    utxos.add(1, 5, true);
+    let stats = utxos.stats();
+    assert_eq!((stats.count, stats.dust_count, stats.median_amount), (4, 1, 5 * KAS));
+    assert_eq!((stats.min_amount, stats.max_amount, stats.oldest_block_daa_score), (KAS, 9 * KAS, 5));
+
+    // Estimated from the sample above it, amounts 1..=100_000 in a scattered order
+    let count = 100 * MEDIAN_SAMPLE_SIZE as u64;
+    let mut utxos = ScriptUtxos::default();
+    (0..count).for_each(|i| utxos.add(i * 7_919 % count + 1, i, false));
+    let stats = utxos.stats();
+    assert_eq!((stats.count, stats.amount, stats.min_amount, stats.max_amount), (count, count * (count + 1) / 2, 1, count));
+    assert!(stats.median_amount.abs_diff(count / 2) < count / 20, "median {}", stats.median_amount);
+}
+
+#[test]
+fn zero_balances_without_dust_filter() {
+    let mut fixture = Fixture::new(100);
+    fixture.add(&p2pk_script(1), 0, 10).add(&p2pk_script(1), 0, 20).add(&p2pk_script(2), 1, 30);
+    let mut options = options(vec![]);
+    options.ignore_dust_amounts = 0;
+    let result = run(&fixture.source(), options);
+    assert_eq!((result.utxo_count, result.total_amount, result.dust_count), (3, 1, 0));
+    assert_eq!(result.script_amounts[&p2pk_script(1)], (0, 2));
+    assert_eq!(tier_counts(&result.utxo_tiers[0]), vec![(0, 3, 1, 3)]);
+    let aggregation = aggregated(&result, &aggregation_options(1), |_| ScriptClass::default());
+    assert_eq!(top_scripts(&aggregation), vec![(p2pk_script(2), 1)]);
+    assert_eq!(result.top_utxos.iter().map(|u| u.0).collect::<Vec<_>>(), vec![1, 0, 0]);
+}
+
+#[test]
+fn empty_set() {
+    let result = run(&Fixture::new(100).source(), options(vec![p2pk_script(1)]));
+    assert_eq!((result.utxo_count, result.total_amount, result.dust_count), (0, 0, 0));
+    assert!(result.script_amounts.is_empty() && result.top_utxos.is_empty() && result.script_utxos.is_empty());
+    assert_eq!(result.watched_dust[&p2pk_script(1)], (0, 0));
+    assert!(tier_counts(&result.utxo_tiers[0]).is_empty());
+}
+
+#[test]
+fn dump_file_matches_memory() {
+    let fixture = fixture();
+    let path = std::env::temp_dir().join(format!("utxo-fixture-{}.dump", std::process::id()));
+    assert_eq!(fixture.write_dump(&path, "mainnet").unwrap(), 14);
+    let file = DumpFileSource::open(&path).unwrap();
+    assert_eq!(file.header().network, "mainnet");
+    assert_eq!(file.anchor().daa_score, fixture.anchor().daa_score);
+
+    let from_file = run(&file, options(vec![p2sh_script(3)]));
+    let from_memory = run(&fixture.source(), options(vec![p2sh_script(3)]));
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(from_file.script_amounts, from_memory.script_amounts);
+    assert_eq!(from_file.top_utxos, from_memory.top_utxos);
+    assert_eq!(from_file.watched_dust, from_memory.watched_dust);
+    assert_eq!(tier_counts(&from_file.utxo_tiers[0]), tier_counts(&from_memory.utxo_tiers[0]));
+}
+
+#[test]
+fn corrupted_dump_fails_the_checksum() {
+    let path = std::env::temp_dir().join(format!("utxo-corrupted-{}.dump", std::process::id()));
+    fixture().write_dump(&path, "mainnet").unwrap();
+    // Flip a bit of the anchor hash, the header is stored uncompressed so only the checksum catches it
+    let mut bytes = std::fs::read(&path).unwrap();
+    let offset = bytes.windows(32).position(|w| w == [0xab; 32]).unwrap();
+    bytes[offset] ^= 1;
+    std::fs::write(&path, &bytes).unwrap();
+    let file = DumpFileSource::open(&path).unwrap();
+    let error = scan(&file, options(vec![]), &AtomicBool::new(true)).err().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(error.to_string().starts_with("Dump checksum mismatch"), "{error}");
+}
+
+#[test]
+fn oversized_script_in_dump() {
+    let header = DumpHeader {
+        network: "mainnet".to_string(),
+        anchor_mode: "virtual".to_string(),
+        anchor_hash: [0; 32],
+        daa_score: 0,
+        created_ms: 0,
+    };
+    let mut bytes = vec![];
+    DumpWriter::new(&mut bytes, &header).unwrap().finish().unwrap();
+    // Keep the uncompressed header, followed by a record claiming a 4 GiB script
+    bytes.truncate(8 + 2 + 1 + 7 + 1 + 7 + 32 + 8 + 8);
+    let mut record = vec![1u8];
+    record.extend_from_slice(&[0; 32 + 4 + 8 + 8 + 1 + 2]);
+    record.extend_from_slice(&u32::MAX.to_le_bytes());
+    let mut encoder = GzEncoder::new(bytes, Compression::default());
+    encoder.write_all(&record).unwrap();
+    let bytes = encoder.finish().unwrap();
+
+    let error = DumpReader::new(bytes.as_slice()).unwrap().next().unwrap().unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(error.to_string(), format!("Script of {} bytes exceeds {MAX_SCRIPT_LEN}", u32::MAX));
+
+    let mut writer = DumpWriter::new(vec![], &header).unwrap();
+    let record = UtxoRecord {
+        transaction_id: [0; 32],
+        index: 0,
+        amount: 1,
+        block_daa_score: 0,
+        is_coinbase: false,
+        script_version: 0,
+        script: vec![0; MAX_SCRIPT_LEN + 1],
+    };
+    assert_eq!(writer.write(&record).unwrap_err().kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn cancelled_scan() {
+    let error = scan(&fixture().source(), options(vec![]), &AtomicBool::new(false)).err().unwrap();
+    assert!(error.downcast_ref::<ScanCancelled>().is_some());
+}
@@ -0,0 +1,49 @@
+use simply_kaspa_utxo_exporter_scan::tier_scheme::TierScheme;
+use std::str::FromStr;
+
+#[test]
+fn log_scheme_boundaries() {
+    let scheme = TierScheme::from_str("default=log:10:100000000").unwrap();
+    assert_eq!(scheme.name, "default");
+    assert_eq!(scheme.boundaries.len(), 11);
+    assert_eq!(scheme.boundaries.first(), Some(&100_000_000));
+    assert_eq!(scheme.boundaries.last(), Some(&1_000_000_000_000_000_000));
+    assert!(scheme.boundaries.windows(2).all(|w| w[1] == w[0] * 10));
+    assert_eq!(scheme.tier_count(), 12);
+}
+
+#[test]
+fn log_scheme_skips_duplicate_boundaries() {
+    let scheme = TierScheme::from_str("fine=log:1.1:1").unwrap();
+    assert_eq!(&scheme.boundaries[..6], &[1, 2, 3, 4, 5, 6]);
+    assert!(scheme.boundaries.windows(2).all(|w| w[0] < w[1]));
+    assert!(*scheme.boundaries.last().unwrap() <= i64::MAX as u64);
+}
+
+#[test]
+fn explicit_boundaries() {
+    let scheme = TierScheme::from_str(" custom = 10, 100 ,1000").unwrap();
+    assert_eq!(scheme, TierScheme { name: "custom".to_string(), boundaries: vec![10, 100, 1000] });
+    assert_eq!(scheme.to_string(), "custom (4 tiers)");
+}
+
+#[test]
+fn tier_math() {
+    let scheme = TierScheme::from_str("custom=10,100,1000").unwrap();
+    let tiers: Vec<usize> = [0, 9, 10, 11, 99, 100, 999, 1000, u64::MAX].iter().map(|&a| scheme.tier(a)).collect();
+    assert_eq!(tiers, vec![0, 0, 1, 1, 1, 2, 2, 3, 3]);
+    assert_eq!(scheme.bounds(0), (0, Some(10)));
+    assert_eq!(scheme.bounds(2), (100, Some(1000)));
+    assert_eq!(scheme.bounds(3), (1000, None));
+}
+
+#[test]
+fn invalid_schemes() {
+    for s in
+        ["no-definition", "=10,100", "a=100,10", "a=10,10", "a=10,x", "a=", "a=log:1:100", "a=log:10:0", "a=log:10", "a=log:x:100"]
+    {
+        assert!(TierScheme::from_str(s).is_err(), "{s} should be rejected");
+    }
+    let too_many = (1..=i16::MAX as u64).map(|b| b.to_string()).collect::<Vec<_>>().join(",");
+    assert!(TierScheme::from_str(&format!("a={too_many}")).is_err());
+}